@@ -17,6 +17,19 @@
 
 //! A set of constant values used in substrate runtime.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// The first value in production and the second one with the `fast-runtime` feature.
+///
+/// `cfg!` is expanded in the calling crate, so it must forward its own `fast-runtime` feature to
+/// `node-constants/fast-runtime` for both to agree.
+#[macro_export]
+macro_rules! prod_or_fast {
+	($prod:expr, $fast:expr) => {
+		if cfg!(feature = "fast-runtime") { $fast } else { $prod }
+	};
+}
+
 /// Money matters.
 pub mod currency {
 	use node_primitives::Balance;
@@ -25,6 +38,11 @@ pub mod currency {
 	pub const CENTS: Balance = 1_000 * MILLICENTS;    // assume this is worth about a cent.
 	pub const DOLLARS: Balance = 100 * CENTS;
 
+	/// Symbol of the token, worth one `DOLLARS`.
+	pub const TOKEN_SYMBOL: &str = "UNIT";
+	/// Number of decimals of the token: `DOLLARS` is `10^TOKEN_DECIMALS`.
+	pub const TOKEN_DECIMALS: u8 = 14;
+
 	pub const fn deposit(items: u32, bytes: u32) -> Balance {
 		items as Balance * 15 * CENTS + (bytes as Balance) * 6 * CENTS
 	}
@@ -51,7 +69,7 @@ pub mod time {
 	/// `SLOT_DURATION` should have the same value.
 	///
 	/// <https://research.web3.foundation/en/latest/polkadot/block-production/Babe.html#-6.-practical-results>
-	pub const MILLISECS_PER_BLOCK: Moment = 3000;
+	pub const MILLISECS_PER_BLOCK: Moment = prod_or_fast!(6000, 2000);
 	pub const SECS_PER_BLOCK: Moment = MILLISECS_PER_BLOCK / 1000;
 
 	pub const SLOT_DURATION: Moment = MILLISECS_PER_BLOCK;
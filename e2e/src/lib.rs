@@ -0,0 +1,231 @@
+//! In-process end-to-end tests of the node.
+//!
+//! A [`TestNode`] runs the node's full service, or its development service with `--sealing`, in
+//! the test process on a temporary database, with the `dev` chain spec and Alice as the only
+//! authority. Tests talk to it over its HTTP RPC like any client would, so the RPC wiring is
+//! covered along with consensus.
+
+use std::{
+	net::TcpListener,
+	thread,
+	time::{Duration, Instant},
+};
+use codec::{Decode, Encode};
+use frame_system::{EventRecord, Phase};
+use futures::FutureExt;
+use node_template::{chain_spec, sealing::Sealing, service, tx::{self, RpcClient, Signer}};
+use node_template_runtime::{Call, Event, Hash};
+use sc_cli::{ChainSpec, RunCmd, RuntimeVersion, SubstrateCli};
+use sc_service::{TaskExecutor, TaskManager, TaskType};
+use serde_json::{json, Value};
+use sp_core::{hashing::{blake2_256, twox_128}, Bytes};
+use sp_keyring::AccountKeyring;
+use structopt::StructOpt;
+
+/// How long to wait for the node before failing the test.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Builds the configuration of test nodes.
+struct TestCli;
+
+impl SubstrateCli for TestCli {
+	fn impl_name() -> String {
+		"Substrate Node E2E".into()
+	}
+
+	fn impl_version() -> String {
+		env!("CARGO_PKG_VERSION").into()
+	}
+
+	fn description() -> String {
+		env!("CARGO_PKG_DESCRIPTION").into()
+	}
+
+	fn author() -> String {
+		env!("CARGO_PKG_AUTHORS").into()
+	}
+
+	fn support_url() -> String {
+		"support.anonymous.an".into()
+	}
+
+	fn copyright_start_year() -> i32 {
+		2017
+	}
+
+	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		match id {
+			"dev" => Ok(Box::new(chain_spec::development_config()?)),
+			id => Err(format!("Test nodes only run the `dev` chain, not `{}`", id)),
+		}
+	}
+
+	fn native_runtime_version(_: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
+		&node_template_runtime::VERSION
+	}
+}
+
+/// A node running in the test process, stopped when dropped.
+pub struct TestNode {
+	task_manager: Option<TaskManager>,
+	runtime: Option<tokio::runtime::Runtime>,
+	rpc: RpcClient,
+}
+
+impl TestNode {
+	/// A full node authoring with Aura and finalizing with GRANDPA.
+	pub fn full() -> Self {
+		Self::start(None)
+	}
+
+	/// A development node authoring and finalizing blocks with `sealing`.
+	pub fn sealing(sealing: Sealing) -> Self {
+		Self::start(Some(sealing))
+	}
+
+	fn start(sealing: Option<Sealing>) -> Self {
+		let rpc_port = free_port();
+		let args = vec![
+			"node-template".to_string(),
+			"--dev".into(),
+			"--tmp".into(),
+			"--no-mdns".into(),
+			"--no-prometheus".into(),
+			"--no-telemetry".into(),
+			"--port".into(), free_port().to_string(),
+			"--rpc-port".into(), rpc_port.to_string(),
+			"--ws-port".into(), free_port().to_string(),
+		];
+		let cmd = RunCmd::from_iter(args);
+
+		let mut runtime = tokio::runtime::Builder::new()
+			.threaded_scheduler()
+			.enable_all()
+			.build()
+			.expect("Unable to start the tokio runtime");
+		let handle = runtime.handle().clone();
+		let task_executor = move |fut, task_type| match task_type {
+			TaskType::Async => handle.spawn(fut).map(drop),
+			TaskType::Blocking => handle.spawn_blocking(move || futures::executor::block_on(fut))
+				.map(drop),
+		};
+		let config = TestCli.create_configuration(&cmd, TaskExecutor::from(task_executor))
+			.expect("Invalid test node configuration");
+
+		let task_manager = runtime.block_on(async move {
+			match sealing {
+				Some(sealing) => service::new_dev(config, sealing),
+				None => service::new_full(config, Default::default()),
+			}
+		}).expect("Unable to start the test node");
+
+		let node = TestNode {
+			task_manager: Some(task_manager),
+			runtime: Some(runtime),
+			rpc: RpcClient::new(&format!("http://127.0.0.1:{}", rpc_port)),
+		};
+		node.wait_for("the RPC server", || node.rpc.call::<Value>("system_health", json!([])).ok());
+		node
+	}
+
+	/// Client of the node's HTTP RPC.
+	pub fn rpc(&self) -> &RpcClient {
+		&self.rpc
+	}
+
+	/// Sign `call` with `signer` and submit it to the pool, returning the extrinsic's hash.
+	pub fn submit(&self, signer: AccountKeyring, call: Call) -> Hash {
+		let signer = Signer::Pair(signer.pair());
+		let state = self.rpc.chain_state(&signer.account(), 0)
+			.expect("Unable to read the chain state");
+		let xt = tx::build(call, &signer, &state, 0).expect("Signing with a key pair never fails");
+		self.rpc.call("author_submitExtrinsic", json!([Bytes(xt.encode())]))
+			.expect("The extrinsic was rejected")
+	}
+
+	/// Submit `call` signed by `signer`, wait until the block including it is finalized, and
+	/// return the events the extrinsic emitted.
+	pub fn submit_and_finalize(&self, signer: AccountKeyring, call: Call) -> Vec<Event> {
+		let from = self.finalized_number();
+		let hash = self.submit(signer, call);
+		let (block, index) = self.wait_for("the extrinsic to be finalized", || {
+			(from..=self.finalized_number()).find_map(|number| self.find_extrinsic(number, hash))
+		});
+		self.events(block, index)
+	}
+
+	/// Wait until block `number` is finalized.
+	pub fn wait_for_finalized(&self, number: u64) {
+		self.wait_for(&format!("block #{} to be finalized", number), || {
+			Some(()).filter(|_| self.finalized_number() >= number)
+		})
+	}
+
+	/// Number of the last finalized block.
+	pub fn finalized_number(&self) -> u64 {
+		let hash: Hash = self.rpc.call("chain_getFinalizedHead", json!([]))
+			.expect("Unable to read the finalized head");
+		let header: Value = self.rpc.call("chain_getHeader", json!([hash]))
+			.expect("Unable to read the finalized header");
+		header["number"].as_str()
+			.and_then(|n| u64::from_str_radix(n.trim_start_matches("0x"), 16).ok())
+			.expect("Invalid header number")
+	}
+
+	/// Hash of block `number` and index in it of the extrinsic `hash`, if it is there.
+	fn find_extrinsic(&self, number: u64, hash: Hash) -> Option<(Hash, usize)> {
+		let block_hash = self.rpc.block_hash(number).ok()?;
+		let block: Value = self.rpc.call("chain_getBlock", json!([block_hash])).ok()?;
+		let extrinsics: Vec<Bytes> = serde_json::from_value(block["block"]["extrinsics"].clone())
+			.ok()?;
+		extrinsics.iter()
+			.position(|xt| Hash::from(blake2_256(&xt.0)) == hash)
+			.map(|index| (block_hash, index))
+	}
+
+	/// Events emitted by the extrinsic at `index` of block `block`.
+	fn events(&self, block: Hash, index: usize) -> Vec<Event> {
+		let key = Bytes([twox_128(b"System"), twox_128(b"Events")].concat());
+		let events: Option<Bytes> = self.rpc.call("state_getStorage", json!([key, block]))
+			.expect("Unable to read the events");
+		let records = events
+			.map(|events| Vec::<EventRecord<Event, Hash>>::decode(&mut &events.0[..]))
+			.transpose()
+			.expect("Invalid events")
+			.unwrap_or_default();
+		records.into_iter()
+			.filter(|record| record.phase == Phase::ApplyExtrinsic(index as u32))
+			.map(|record| record.event)
+			.collect()
+	}
+
+	/// Poll `f` until it returns `Some`, panicking after `TIMEOUT`.
+	fn wait_for<T>(&self, what: &str, mut f: impl FnMut() -> Option<T>) -> T {
+		let start = Instant::now();
+		loop {
+			if let Some(result) = f() {
+				return result
+			}
+			assert!(start.elapsed() < TIMEOUT, "Timed out waiting for {}", what);
+			thread::sleep(Duration::from_millis(100));
+		}
+	}
+}
+
+impl Drop for TestNode {
+	fn drop(&mut self) {
+		// Stop the node's tasks before the runtime they run on.
+		drop(self.task_manager.take());
+		if let Some(runtime) = self.runtime.take() {
+			runtime.shutdown_timeout(Duration::from_secs(5));
+		}
+	}
+}
+
+/// A port nothing listens on, for the node to bind to.
+fn free_port() -> u16 {
+	TcpListener::bind("127.0.0.1:0")
+		.and_then(|listener| listener.local_addr())
+		.map(|addr| addr.port())
+		.expect("No free port")
+}
@@ -0,0 +1,48 @@
+//! Transfers submitted over RPC are authored, finalized and emit their events.
+
+use node_template::sealing::Sealing;
+use node_template_e2e::TestNode;
+use node_template_runtime::{node_constants::currency::DOLLARS, BalancesCall, Call, Event};
+use serde_json::json;
+use sp_core::crypto::Ss58Codec;
+use sp_keyring::AccountKeyring::{self, Alice, Bob, Dave, Eve};
+
+fn transfer(to: AccountKeyring, amount: u128) -> Call {
+	Call::Balances(BalancesCall::transfer(to.to_account_id().into(), amount))
+}
+
+#[test]
+fn full_node_finalizes_transfers() {
+	let node = TestNode::full();
+	node.wait_for_finalized(1);
+
+	let events = node.submit_and_finalize(Alice, transfer(Dave, DOLLARS));
+
+	assert!(events.contains(&Event::pallet_balances(
+		pallet_balances::Event::Endowed(Dave.to_account_id(), DOLLARS)
+	)));
+	assert!(events.contains(&Event::pallet_balances(
+		pallet_balances::Event::Transfer(Alice.to_account_id(), Dave.to_account_id(), DOLLARS)
+	)));
+	assert!(events.iter().any(|event| matches!(
+		event, Event::frame_system(frame_system::Event::ExtrinsicSuccess(_))
+	)));
+}
+
+#[test]
+fn instant_sealing_finalizes_every_transaction() {
+	let node = TestNode::sealing(Sealing::Instant);
+
+	for nonce in 0..3u32 {
+		let events = node.submit_and_finalize(Bob, transfer(Eve, DOLLARS));
+		assert!(events.iter().any(|event| matches!(
+			event, Event::pallet_balances(pallet_balances::Event::Transfer(..))
+		)));
+
+		let next: u32 = node.rpc()
+			.call("system_accountNextIndex", json!([Bob.to_account_id().to_ss58check()]))
+			.unwrap();
+		assert_eq!(next, nonce + 1);
+	}
+	assert_eq!(node.finalized_number(), 3);
+}
@@ -0,0 +1,144 @@
+//! Aura authoring backoff while finality lags, and its Prometheus metrics.
+
+use std::{sync::Arc, time::{SystemTime, UNIX_EPOCH}};
+use futures::{future, stream, Future, StreamExt};
+use node_template_runtime::{opaque::Block, BlockNumber};
+use sc_client_api::BlockchainEvents;
+use sc_consensus_slots::{BackoffAuthoringBlocksStrategy, BackoffAuthoringOnFinalizedHeadLagging};
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
+use sp_runtime::generic::BlockId;
+use substrate_prometheus_endpoint::{register, Gauge, PrometheusError, Registry, U64};
+use crate::chain_spec::BackoffParams;
+
+const LOG_TARGET: &str = "authoring-backoff-metrics";
+
+/// The backoff strategy given to Aura. `params` must have been validated, as Substrate divides by
+/// the authoring bias.
+pub fn strategy(params: &BackoffParams) -> BackoffAuthoringOnFinalizedHeadLagging<BlockNumber> {
+	BackoffAuthoringOnFinalizedHeadLagging {
+		max_interval: params.max_interval,
+		unfinalized_slack: params.unfinalized_slack,
+		authoring_bias: params.authoring_bias,
+	}
+}
+
+/// Number of slots past the best block an author waits for, given the best and finalized block
+/// numbers. Zero when not backing off.
+///
+/// Mirrors `BackoffAuthoringOnFinalizedHeadLagging::should_backoff`, and like it expects
+/// `params` to have been validated.
+pub fn interval(params: &BackoffParams, best: BlockNumber, finalized: BlockNumber) -> u32 {
+	let unfinalized = best.saturating_sub(finalized);
+	let interval = unfinalized.saturating_sub(params.unfinalized_slack) / params.authoring_bias;
+	interval.min(params.max_interval)
+}
+
+/// Whether an author skips `slot_now`, given the best and finalized block numbers and the slot of
+/// the best block, as decided by the strategy given to Aura.
+///
+/// The strategy logs at info level whenever it backs off, under the `authoring-backoff-metrics`
+/// target here.
+pub fn is_active(
+	params: &BackoffParams,
+	best: BlockNumber,
+	best_slot: u64,
+	finalized: BlockNumber,
+	slot_now: u64,
+) -> bool {
+	// The finalized block may briefly be reported ahead of the best one, which the strategy does
+	// not expect.
+	let finalized = finalized.min(best);
+	strategy(params).should_backoff(best, best_slot, finalized, slot_now, LOG_TARGET)
+}
+
+struct Metrics {
+	active: Gauge<U64>,
+	interval: Gauge<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Metrics {
+			active: register(
+				Gauge::new(
+					"aura_authoring_backoff_active",
+					"Whether the current slot is skipped because finality lags (0 or 1).",
+				)?,
+				registry,
+			)?,
+			interval: register(
+				Gauge::new(
+					"aura_authoring_backoff_interval",
+					"Number of slots past the best block authors wait for before authoring.",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Keep the backoff gauges up to date as blocks are imported and finalized, on a chain with
+/// `slot_duration` milliseconds per slot.
+pub fn monitor<C>(
+	client: Arc<C>,
+	params: BackoffParams,
+	slot_duration: u64,
+	registry: &Registry,
+) -> Result<impl Future<Output = ()>, PrometheusError> where
+	C: BlockchainEvents<Block> + HeaderBackend<Block>,
+{
+	let metrics = Metrics::register(registry)?;
+	let imported = client.import_notification_stream().map(|_| ());
+	let finalized = client.finality_notification_stream().map(|_| ());
+	Ok(stream::select(imported, finalized).for_each(move |()| {
+		let info = client.info();
+		let best_slot = client.header(BlockId::Hash(info.best_hash))
+			.ok()
+			.flatten()
+			.and_then(|header| sc_consensus_aura::find_pre_digest::<Block, AuraPair>(&header).ok());
+		let active = best_slot.map_or(false, |best_slot| is_active(
+			&params,
+			info.best_number,
+			best_slot,
+			info.finalized_number,
+			current_slot(slot_duration),
+		));
+		metrics.active.set(active as u64);
+		metrics.interval.set(interval(&params, info.best_number, info.finalized_number) as u64);
+		future::ready(())
+	}))
+}
+
+/// The slot of the wall clock, as Aura computes it from the timestamp.
+fn current_slot(slot_duration: u64) -> u64 {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+	now.as_millis() as u64 / slot_duration
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn interval_grows_with_the_finality_lag() {
+		let params = BackoffParams::default();
+		assert_eq!(interval(&params, 50, 0), 0);
+		assert_eq!(interval(&params, 1_050, 1_000), 0);
+		assert_eq!(interval(&params, 60, 0), 5);
+		assert_eq!(interval(&params, 10_000, 0), params.max_interval);
+		// The finalized block may briefly be reported ahead of the best one.
+		assert_eq!(interval(&params, 10, 11), 0);
+	}
+
+	#[test]
+	fn backoff_is_active_until_the_interval_has_passed() {
+		let params = BackoffParams::default();
+		// Within the slack, authors never skip a slot.
+		assert!(!is_active(&params, 50, 100, 0, 101));
+		// 5 slots past the best block are skipped, the sixth is claimed again.
+		assert!(is_active(&params, 60, 100, 0, 105));
+		assert!(!is_active(&params, 60, 100, 0, 106));
+		assert!(!is_active(&params, 10, 100, 11, 101));
+	}
+}
@@ -1,18 +1,140 @@
-use sp_core::{Pair, Public, sr25519};
+use std::{collections::BTreeSet, path::Path};
+use serde::{Deserialize, Serialize};
+use sc_chain_spec::ChainSpecExtension;
+use sp_core::{Get, Pair, Public, sr25519, crypto::Ss58Codec};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AuraConfig, Balance, BalancesConfig, ExistentialDeposit, GenesisConfig,
+	GrandpaConfig, SS58Prefix, SudoConfig, SystemConfig, WASM_BINARY, Signature,
+	node_constants::currency::{DOLLARS, TOKEN_DECIMALS, TOKEN_SYMBOL},
+	node_primitives::balance::Unit,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
-use sc_service::ChainType;
+use sc_service::{ChainType, config::MultiaddrWithPeerId};
+use sc_telemetry::TelemetryEndpoints;
 
-// The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+/// The network config of the staging network, see `res/staging.toml`.
+const STAGING_CONFIG: &str = include_str!("../res/staging.toml");
+
+/// Balance of the accounts endowed on the development chains.
+const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
+
+/// Names of the well-known development accounts, whose keys are derived from `//<name>` and
+/// `//<name>//stash` and can therefore be used by anyone.
+const DEV_NAMES: &[&str] = &["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Node-side parameters of a chain, stored in its chain spec next to the genesis.
+///
+/// Every field has a default, so chain specs without extensions keep working.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct Extensions {
+	/// GRANDPA voter parameters.
+	pub grandpa: GrandpaParams,
+	/// Aura authoring parameters.
+	pub aura: AuraParams,
+}
+
+impl Extensions {
+	/// The extensions of `chain_spec`, if it was built for this node.
+	pub fn try_get(chain_spec: &dyn sc_service::ChainSpec) -> Option<&Self> {
+		sc_chain_spec::get_extension(chain_spec.extensions())
+	}
+}
+
+/// GRANDPA voter parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct GrandpaParams {
+	/// Interval between gossip rounds, in milliseconds.
+	pub gossip_duration_ms: u64,
+	/// Number of blocks between justifications stored for light clients and warp sync.
+	pub justification_period: u32,
+	/// Rules restricting which block voters vote for.
+	pub voting_rules: VotingRules,
+}
+
+impl Default for GrandpaParams {
+	fn default() -> Self {
+		GrandpaParams {
+			gossip_duration_ms: 333,
+			justification_period: 512,
+			voting_rules: Default::default(),
+		}
+	}
+}
+
+/// Voting rules applied by GRANDPA voters, on top of voting for the best block.
+///
+/// The defaults are those of `VotingRulesBuilder::default()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct VotingRules {
+	/// Vote at least this many blocks behind the best block, if any.
+	pub before_best_block_by: Option<u32>,
+	/// Vote for at most three quarters of the unfinalized chain.
+	pub three_quarters_of_unfinalized_chain: bool,
+	/// Never vote more than this many blocks past the last finalized block, if any.
+	pub max_votes_ahead: Option<u32>,
+}
+
+impl Default for VotingRules {
+	fn default() -> Self {
+		VotingRules {
+			before_best_block_by: Some(2),
+			three_quarters_of_unfinalized_chain: true,
+			max_votes_ahead: None,
+		}
+	}
+}
+
+/// Aura authoring parameters.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct AuraParams {
+	/// Author blocks even while offline or syncing, as `--force-authoring` does. Meant for chains
+	/// with a single authority.
+	pub force_authoring: bool,
+	/// Skip authoring slots while finality lags behind the best block, if set.
+	pub backoff: Option<BackoffParams>,
+}
+
+/// Parameters of the authoring backoff while finality lags.
+///
+/// Once more than `unfinalized_slack` blocks are unfinalized, an author skips slots until the
+/// slot is `(unfinalized - unfinalized_slack) / authoring_bias` slots past the best block, but at
+/// most `max_interval` slots. The defaults are those of Substrate's
+/// `BackoffAuthoringOnFinalizedHeadLagging`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct BackoffParams {
+	/// Maximum number of slots skipped.
+	pub max_interval: u32,
+	/// Number of unfinalized blocks tolerated before backing off.
+	pub unfinalized_slack: u32,
+	/// Number of unfinalized blocks past the slack per skipped slot.
+	pub authoring_bias: u32,
+}
+
+impl Default for BackoffParams {
+	fn default() -> Self {
+		BackoffParams { max_interval: 100, unfinalized_slack: 50, authoring_bias: 2 }
+	}
+}
+
+impl BackoffParams {
+	/// Check that Substrate's backoff can use the parameters: it divides by `authoring_bias`.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.authoring_bias == 0 {
+			return Err("The `authoringBias` of the authoring backoff must be at least 1".into())
+		}
+		Ok(())
+	}
+}
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -71,9 +193,9 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(token_properties(&token_unit())),
 		// Extensions
-		None,
+		Default::default(),
 	))
 }
 
@@ -119,12 +241,271 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(token_properties(&token_unit())),
 		// Extensions
-		None,
+		Default::default(),
 	))
 }
 
+/// The token of the runtime, as named on the development chains.
+pub fn token_unit() -> Unit {
+	Unit::new(TOKEN_DECIMALS, TOKEN_SYMBOL)
+}
+
+/// The token of `spec`, from its properties, or that of the development chains if it does not
+/// name one.
+pub fn token_unit_of(spec: &dyn sc_service::ChainSpec) -> Unit {
+	serde_json::from_value(spec.properties().into()).unwrap_or_else(|_| token_unit())
+}
+
+/// The chain spec properties naming the token of `unit`.
+fn token_properties(unit: &Unit) -> sc_service::Properties {
+	let mut properties = sc_service::Properties::new();
+	properties.insert("tokenSymbol".into(), unit.symbol.clone().into());
+	properties.insert("tokenDecimals".into(), unit.decimals.into());
+	properties
+}
+
+/// The staging network, built from the config bundled in `res/staging.toml`.
+///
+/// The bundled config only holds placeholders for keys and accounts, so this fails until they
+/// are replaced with those of the network.
+pub fn staging_config() -> Result<ChainSpec, String> {
+	NetworkConfig::from_toml(STAGING_CONFIG)
+		.map_err(|e| format!("Invalid bundled staging config: {}", e))?
+		.build()
+}
+
+/// A network described by a config file, e.g. `--chain config:mainnet.toml`.
+///
+/// The format is chosen by the file extension, `.toml` or `.json`.
+pub fn config_file_spec(path: &Path) -> Result<ChainSpec, String> {
+	let contents = std::fs::read_to_string(path)
+		.map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+	let config = match path.extension().and_then(|ext| ext.to_str()) {
+		Some("toml") => NetworkConfig::from_toml(&contents),
+		Some("json") => NetworkConfig::from_json(&contents),
+		_ => Err("expected a .toml or .json file".into()),
+	}.map_err(|e| format!("Invalid network config {}: {}", path.display(), e))?;
+	config.build()
+}
+
+/// Everything that differs between live networks, read from a TOML or JSON file.
+///
+/// Keys and accounts are SS58 encoded. Balances are given as strings, as neither format can
+/// represent every `u128`. The token decimals and SS58 format are not configurable: they are
+/// those of the runtime, and written to the properties of the chain spec.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct NetworkConfig {
+	/// Human readable name of the network.
+	pub name: String,
+	/// Id of the network, also the default name of its database directory.
+	pub id: String,
+	/// `Live` for production networks, `Local` for staging ones.
+	pub chain_type: ChainType,
+	/// Network protocol id, distinct from that of every other network.
+	pub protocol_id: Option<String>,
+	/// Token symbol shown by wallets.
+	pub token_symbol: String,
+	/// Multiaddresses, including peer ids, of the bootnodes.
+	#[serde(default)]
+	pub bootnodes: Vec<String>,
+	/// Telemetry endpoints.
+	#[serde(default)]
+	pub telemetry: Vec<TelemetryConfig>,
+	/// The initial PoA authorities.
+	pub authorities: Vec<AuthorityConfig>,
+	/// The sudo account.
+	pub sudo: String,
+	/// Pre-funded accounts.
+	#[serde(default)]
+	pub endowments: Vec<EndowmentConfig>,
+	/// Node-side parameters of the network.
+	#[serde(default)]
+	pub extensions: Extensions,
+}
+
+/// The session keys of an initial authority.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthorityConfig {
+	/// sr25519 Aura key.
+	pub aura: String,
+	/// ed25519 GRANDPA key.
+	pub grandpa: String,
+}
+
+/// A telemetry endpoint.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryConfig {
+	/// Multiaddress of the endpoint.
+	pub url: String,
+	/// Verbosity of the telemetry sent to it.
+	pub verbosity: u8,
+}
+
+/// A pre-funded account.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EndowmentConfig {
+	/// The account.
+	pub account: String,
+	/// Its free balance at genesis, in tokens such as `1000 UNIT`.
+	pub balance: String,
+}
+
+/// A `NetworkConfig` whose keys, accounts and balances were parsed and checked.
+struct ValidNetworkConfig {
+	bootnodes: Vec<MultiaddrWithPeerId>,
+	telemetry: Option<TelemetryEndpoints>,
+	authorities: Vec<(AuraId, GrandpaId)>,
+	sudo: AccountId,
+	endowments: Vec<(AccountId, Balance)>,
+}
+
+impl NetworkConfig {
+	/// Parse a TOML network config.
+	pub fn from_toml(contents: &str) -> Result<Self, String> {
+		toml::from_str(contents).map_err(|e| e.to_string())
+	}
+
+	/// Parse a JSON network config.
+	pub fn from_json(contents: &str) -> Result<Self, String> {
+		serde_json::from_str(contents).map_err(|e| e.to_string())
+	}
+
+	/// Check the config and parse its keys, accounts and balances.
+	///
+	/// Unless the network is a development one, the well-known development keys are rejected.
+	fn validate(&self) -> Result<ValidNetworkConfig, String> {
+		if self.name.is_empty() || self.id.is_empty() {
+			return Err("`name` and `id` must not be empty".into())
+		}
+		if self.token_symbol.is_empty() {
+			return Err("`tokenSymbol` must not be empty".into())
+		}
+		if let Some(backoff) = &self.extensions.aura.backoff {
+			backoff.validate()?;
+		}
+
+		let bootnodes = self.bootnodes.iter()
+			.map(|addr| addr.parse().map_err(|e| format!("Invalid bootnode `{}`: {}", addr, e)))
+			.collect::<Result<Vec<MultiaddrWithPeerId>, _>>()?;
+		let telemetry = if self.telemetry.is_empty() {
+			None
+		} else {
+			let endpoints = self.telemetry.iter()
+				.map(|endpoint| (endpoint.url.clone(), endpoint.verbosity))
+				.collect();
+			Some(TelemetryEndpoints::new(endpoints)
+				.map_err(|e| format!("Invalid telemetry endpoint: {}", e))?)
+		};
+
+		if self.authorities.is_empty() {
+			return Err("At least one authority is required".into())
+		}
+		let authorities = self.authorities.iter()
+			.map(|keys| Ok((
+				parse_ss58::<AuraId>("Aura key", &keys.aura)?,
+				parse_ss58::<GrandpaId>("GRANDPA key", &keys.grandpa)?,
+			)))
+			.collect::<Result<Vec<_>, String>>()?;
+		let unique_aura = authorities.iter().map(|keys| &keys.0).collect::<BTreeSet<_>>();
+		let unique_grandpa = authorities.iter().map(|keys| &keys.1).collect::<BTreeSet<_>>();
+		if unique_aura.len() != authorities.len() || unique_grandpa.len() != authorities.len() {
+			return Err("Authority keys must be unique".into())
+		}
+
+		let sudo = parse_ss58::<AccountId>("sudo account", &self.sudo)?;
+
+		let unit = Unit::new(TOKEN_DECIMALS, self.token_symbol.clone());
+		let mut endowed = BTreeSet::new();
+		let mut total: Balance = 0;
+		let endowments = self.endowments.iter()
+			.map(|endowment| {
+				let account = parse_ss58::<AccountId>("endowed account", &endowment.account)?;
+				let balance = unit.parse(&endowment.balance)
+					.map_err(|e| format!("Invalid balance `{}`: {}", endowment.balance, e))?;
+				if !endowed.insert(account.clone()) {
+					return Err(format!("Account {} is endowed twice", endowment.account))
+				}
+				if balance < ExistentialDeposit::get() {
+					return Err(format!(
+						"Endowment of {} ({}) is below the existential deposit of {}",
+						endowment.account,
+						unit.format(balance),
+						unit.format(ExistentialDeposit::get()),
+					))
+				}
+				total = total.checked_add(balance)
+					.ok_or_else(|| "Total issuance overflows".to_string())?;
+				Ok((account, balance))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+
+		if self.chain_type != ChainType::Development {
+			for (keys, (aura, grandpa)) in self.authorities.iter().zip(&authorities) {
+				ensure_not_dev("Aura key", &keys.aura, aura, get_from_seed::<AuraId>)?;
+				ensure_not_dev("GRANDPA key", &keys.grandpa, grandpa, get_from_seed::<GrandpaId>)?;
+			}
+			let dev_account = get_account_id_from_seed::<sr25519::Public>;
+			ensure_not_dev("sudo account", &self.sudo, &sudo, dev_account)?;
+			for (endowment, (account, _)) in self.endowments.iter().zip(&endowments) {
+				ensure_not_dev("endowed account", &endowment.account, account, dev_account)?;
+			}
+		}
+
+		Ok(ValidNetworkConfig { bootnodes, telemetry, authorities, sudo, endowments })
+	}
+
+	/// Validate the config and build its chain spec.
+	pub fn build(self) -> Result<ChainSpec, String> {
+		let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+		let ValidNetworkConfig { bootnodes, telemetry, authorities, sudo, endowments } =
+			self.validate()?;
+
+		let mut properties = token_properties(&Unit::new(TOKEN_DECIMALS, self.token_symbol));
+		properties.insert("ss58Format".into(), SS58Prefix::get().into());
+
+		Ok(ChainSpec::from_genesis(
+			&self.name,
+			&self.id,
+			self.chain_type,
+			move || genesis(wasm_binary, authorities.clone(), sudo.clone(), endowments.clone()),
+			bootnodes,
+			telemetry,
+			self.protocol_id.as_deref(),
+			Some(properties),
+			self.extensions,
+		))
+	}
+}
+
+fn parse_ss58<T: Ss58Codec>(what: &str, s: &str) -> Result<T, String> {
+	T::from_ss58check(s).map_err(|e| format!("Invalid {} `{}`: {:?}", what, s, e))
+}
+
+/// Reject `key`, parsed from `s`, if `from_seed` derives it from a development seed.
+fn ensure_not_dev<T: PartialEq>(
+	what: &str,
+	s: &str,
+	key: &T,
+	from_seed: impl Fn(&str) -> T,
+) -> Result<(), String> {
+	let seed = DEV_NAMES.iter()
+		.flat_map(|name| vec![name.to_string(), format!("{}//stash", name)])
+		.find(|seed| from_seed(seed) == *key);
+	match seed {
+		Some(seed) => Err(format!(
+			"The {} `{}` is the well-known development key `//{}`; generate one for the network",
+			what, s, seed,
+		)),
+		None => Ok(()),
+	}
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
@@ -132,6 +513,17 @@ fn testnet_genesis(
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
 	_enable_println: bool,
+) -> GenesisConfig {
+	let endowments = endowed_accounts.into_iter().map(|k| (k, ENDOWMENT)).collect();
+	genesis(wasm_binary, initial_authorities, root_key, endowments)
+}
+
+/// Configure initial storage state for FRAME modules.
+fn genesis(
+	wasm_binary: &[u8],
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	root_key: AccountId,
+	endowments: Vec<(AccountId, Balance)>,
 ) -> GenesisConfig {
 	GenesisConfig {
 		frame_system: Some(SystemConfig {
@@ -140,8 +532,7 @@ fn testnet_genesis(
 			changes_trie_config: Default::default(),
 		}),
 		pallet_balances: Some(BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			balances: endowments,
 		}),
 		pallet_aura: Some(AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -155,3 +546,164 @@ fn testnet_genesis(
 		}),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The bundled staging config, with its placeholders replaced by keys of the network.
+	fn config() -> NetworkConfig {
+		let mut config = NetworkConfig::from_toml(STAGING_CONFIG).unwrap();
+		config.authorities[0].aura = "5C62Ck4UrFPiBtoCmeSrgF7x9yv9mn38446dhCpsi2mLHiFT".into();
+		config.authorities[0].grandpa = "5C7LYpP2ZH3tpKbvVvwiVe54AapxErdPBbvkYhe6y9ZBkqWt".into();
+		config.authorities[1].aura = "5C8etthaGJi5SkQeEDSaK32ABBjkhwDeK9ksQCTLEGM3EH14".into();
+		config.authorities[1].grandpa = "5C9yEy27yLNG5BDMxVwS8RyGBneZB1ouShazFhGZVP8thK5z".into();
+		config.sudo = "5CBHb3LfgN2Shc25gnSHwpvNCPZMe6QAaFR77C5nkVvkAK1o".into();
+		config.endowments[0].account = "5CBHb3LfgN2Shc25gnSHwpvNCPZMe6QAaFR77C5nkVvkAK1o".into();
+		config.endowments[1].account = "5CCbw7fDPPgdL2poR4w9mDsUCzUA7AzRhoFDxgu21cibdUmW".into();
+		config
+	}
+
+	#[test]
+	fn bundled_staging_config_needs_real_keys() {
+		let bundled = NetworkConfig::from_toml(STAGING_CONFIG).unwrap();
+		assert!(bundled.validate().is_err());
+		assert!(staging_config().is_err());
+
+		config().validate().unwrap();
+	}
+
+	#[test]
+	fn json_and_toml_configs_are_equivalent() {
+		let json = r#"{
+			"name": "Staging",
+			"id": "staging",
+			"chainType": "Live",
+			"tokenSymbol": "UNIT",
+			"authorities": [{
+				"aura": "5C62Ck4UrFPiBtoCmeSrgF7x9yv9mn38446dhCpsi2mLHiFT",
+				"grandpa": "5C7LYpP2ZH3tpKbvVvwiVe54AapxErdPBbvkYhe6y9ZBkqWt"
+			}],
+			"sudo": "5CBHb3LfgN2Shc25gnSHwpvNCPZMe6QAaFR77C5nkVvkAK1o",
+			"endowments": [{
+				"account": "5CBHb3LfgN2Shc25gnSHwpvNCPZMe6QAaFR77C5nkVvkAK1o",
+				"balance": "10_000_000 UNIT"
+			}]
+		}"#;
+		let valid = NetworkConfig::from_json(json).unwrap().validate().unwrap();
+		assert_eq!(valid.authorities.len(), 1);
+		assert_eq!(valid.endowments[0].1, 1_000_000_000_000_000_000_000);
+
+		assert!(NetworkConfig::from_json(&json.replace("chainType", "chain_type")).is_err());
+		let with_decimals = json.replace(r#""chainType""#, r#""tokenDecimals": 14, "chainType""#);
+		assert!(NetworkConfig::from_json(&with_decimals).is_err());
+	}
+
+	#[test]
+	fn endowments_are_given_in_tokens() {
+		let mut config = config();
+		config.endowments[0].balance = "12.5 UNIT".into();
+		config.endowments[1].balance = "12.5".into();
+		assert_eq!(
+			config.validate().err(),
+			Some("Invalid balance `12.5`: Amounts must name the token, e.g. `12.5 UNIT`".into()),
+		);
+
+		config.endowments[1].balance = "1000".into();
+		assert_eq!(
+			config.validate().err(),
+			Some("Invalid balance `1000`: Amounts must name the token, e.g. `12.5 UNIT`".into()),
+		);
+
+		config.endowments[1].balance = "0.00000000001 UNIT".into();
+		assert_eq!(
+			config.validate().err(),
+			Some("Endowment of 5CCbw7fDPPgdL2poR4w9mDsUCzUA7AzRhoFDxgu21cibdUmW \
+				(0.00000000001 UNIT) is below the existential deposit of 0.01 UNIT".into()),
+		);
+
+		config.endowments[1].balance = "0.01 UNIT".into();
+		let valid = config.validate().unwrap();
+		assert_eq!(valid.endowments[0].1, 1_250_000_000_000_000);
+		assert_eq!(valid.endowments[1].1, 1_000_000_000_000);
+	}
+
+	#[test]
+	fn missing_extensions_take_their_defaults() {
+		let extensions: Extensions =
+			serde_json::from_str(r#"{ "grandpa": { "justificationPeriod": 64 } }"#).unwrap();
+		assert_eq!(extensions.grandpa.justification_period, 64);
+		assert_eq!(extensions.grandpa.gossip_duration_ms, 333);
+		assert_eq!(extensions.grandpa.voting_rules, VotingRules::default());
+		assert_eq!(extensions.aura, AuraParams::default());
+
+		let spec = development_config().unwrap();
+		let json = sc_service::ChainSpec::as_json(&spec, false).unwrap();
+		let spec = ChainSpec::from_json_bytes(json.into_bytes()).unwrap();
+		assert_eq!(Extensions::try_get(&spec), Some(&Extensions::default()));
+	}
+
+	#[test]
+	fn dev_keys_are_rejected_outside_development_networks() {
+		let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+		let alice_grandpa = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu";
+
+		let mut dev_aura = config();
+		dev_aura.authorities[0].aura = alice.into();
+		assert_eq!(
+			dev_aura.validate().err(),
+			Some(format!(
+				"The Aura key `{}` is the well-known development key `//Alice`; generate one for \
+					the network",
+				alice,
+			)),
+		);
+
+		let mut dev_grandpa = config();
+		dev_grandpa.authorities[1].grandpa = alice_grandpa.into();
+		assert!(dev_grandpa.validate().is_err());
+
+		let mut dev_sudo = config();
+		dev_sudo.sudo = alice.into();
+		assert!(dev_sudo.validate().is_err());
+
+		let mut dev_endowment = config();
+		dev_endowment.endowments[1].account = alice.into();
+		assert!(dev_endowment.validate().is_err());
+
+		dev_endowment.chain_type = ChainType::Development;
+		dev_endowment.validate().unwrap();
+	}
+
+	#[test]
+	fn invalid_configs_are_rejected() {
+		let mut no_authorities = config();
+		no_authorities.authorities.clear();
+		assert!(no_authorities.validate().is_err());
+
+		let mut duplicate_authority = config();
+		let first = AuthorityConfig {
+			aura: duplicate_authority.authorities[0].aura.clone(),
+			grandpa: duplicate_authority.authorities[0].grandpa.clone(),
+		};
+		duplicate_authority.authorities.push(first);
+		assert!(duplicate_authority.validate().is_err());
+
+		let mut bad_sudo = config();
+		bad_sudo.sudo = "5Grwva".into();
+		assert!(bad_sudo.validate().is_err());
+
+		let mut dust = config();
+		dust.endowments[0].balance = "0.00000000000001 UNIT".into();
+		assert!(dust.validate().is_err());
+
+		let mut bad_bootnode = config();
+		bad_bootnode.bootnodes.push("/ip4/127.0.0.1/tcp/30333".into());
+		assert!(bad_bootnode.validate().is_err());
+
+		let mut no_authoring_bias = config();
+		no_authoring_bias.extensions.aura.backoff =
+			Some(BackoffParams { authoring_bias: 0, ..Default::default() });
+		assert!(no_authoring_bias.validate().is_err());
+	}
+}
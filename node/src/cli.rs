@@ -1,5 +1,10 @@
+use std::path::PathBuf;
 use structopt::StructOpt;
-use sc_cli::RunCmd;
+use node_template_runtime::RoomId;
+use sc_cli::{CliConfiguration, ImportParams, RunCmd, SharedParams};
+use crate::sealing::Sealing;
+use crate::chain_spec::BackoffParams;
+use crate::inspect::{BlockAddress, ExtrinsicAddress};
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -8,6 +13,107 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks `instant`ly, `manual`ly over RPC or every `interval=<ms>` instead of running
+	/// Aura and GRANDPA. Only available for development chains.
+	#[structopt(long)]
+	pub sealing: Option<Sealing>,
+
+	/// Follow GRANDPA finality as an observer instead of running the full voter. Saves resources
+	/// on nodes that never vote, e.g. RPC nodes; not available to validators.
+	#[structopt(long)]
+	pub grandpa_observer: bool,
+
+	#[structopt(flatten)]
+	pub backoff: BackoffCmd,
+
+	#[structopt(flatten)]
+	pub remote_keystore: RemoteKeystoreCmd,
+}
+
+/// Authentication between the node and the remote signer of `--keystore-uri`.
+#[derive(Debug, Clone, StructOpt)]
+pub struct RemoteKeystoreCmd {
+	/// File holding the bearer token the node presents to the remote signer. Required with
+	/// `--keystore-uri`.
+	#[structopt(long, parse(from_os_str))]
+	pub keystore_token_file: Option<PathBuf>,
+
+	/// PEM file of the certificate authorities that issued the TLS certificate of the remote
+	/// signer, e.g. a private CA. The web PKI roots are trusted if not given.
+	#[structopt(long, parse(from_os_str))]
+	pub keystore_ca: Option<PathBuf>,
+}
+
+impl RemoteKeystoreCmd {
+	/// The credentials read from the given files, if a token file was given.
+	pub fn credentials(&self) -> Result<Option<remote_keystore::Credentials>, String> {
+		let read = |path: &PathBuf| std::fs::read(path)
+			.map_err(|e| format!("Error reading {}: {}", path.display(), e));
+		let token = match &self.keystore_token_file {
+			Some(path) => String::from_utf8(read(path)?)
+				.map_err(|_| format!("{} is not a UTF-8 token", path.display()))?,
+			None => return Ok(None),
+		};
+		let ca_certificates = self.keystore_ca.as_ref().map(read).transpose()?;
+		Ok(Some(remote_keystore::Credentials { token: token.trim().to_string(), ca_certificates }))
+	}
+}
+
+/// Aura authoring backoff while finality lags, overriding the chain spec.
+#[derive(Debug, Clone, StructOpt)]
+pub struct BackoffCmd {
+	/// Never back off authoring, whatever the chain spec says.
+	#[structopt(long)]
+	pub no_authoring_backoff: bool,
+
+	/// Maximum number of slots skipped while backing off. Enables the backoff.
+	#[structopt(long, conflicts_with = "no-authoring-backoff")]
+	pub backoff_max_interval: Option<u32>,
+
+	/// Number of unfinalized blocks tolerated before backing off. Enables the backoff.
+	#[structopt(long, conflicts_with = "no-authoring-backoff")]
+	pub backoff_unfinalized_slack: Option<u32>,
+
+	/// Number of unfinalized blocks past the slack per skipped slot, at least 1. Enables the
+	/// backoff.
+	#[structopt(
+		long,
+		conflicts_with = "no-authoring-backoff",
+		parse(try_from_str = parse_authoring_bias),
+	)]
+	pub backoff_authoring_bias: Option<u32>,
+}
+
+fn parse_authoring_bias(s: &str) -> Result<u32, String> {
+	match s.parse::<u32>().map_err(|e| e.to_string())? {
+		0 => Err("The authoring bias must be at least 1".into()),
+		bias => Ok(bias),
+	}
+}
+
+impl BackoffCmd {
+	/// The backoff to use given that of the chain spec.
+	///
+	/// Parameters given on the command line override those of the chain spec, or the defaults if
+	/// the chain spec has no backoff.
+	pub fn apply(&self, chain_spec: Option<BackoffParams>) -> Option<BackoffParams> {
+		if self.no_authoring_backoff {
+			return None
+		}
+		let overridden = self.backoff_max_interval.is_some()
+			|| self.backoff_unfinalized_slack.is_some()
+			|| self.backoff_authoring_bias.is_some();
+		if chain_spec.is_none() && !overridden {
+			return None
+		}
+		let params = chain_spec.unwrap_or_default();
+		Some(BackoffParams {
+			max_interval: self.backoff_max_interval.unwrap_or(params.max_interval),
+			unfinalized_slack: self.backoff_unfinalized_slack.unwrap_or(params.unfinalized_slack),
+			authoring_bias: self.backoff_authoring_bias.unwrap_or(params.authoring_bias),
+		})
+	}
 }
 
 #[derive(Debug, StructOpt)]
@@ -26,6 +132,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Turn a chain spec written by `export-state` into a development chain spec.
+	ForkOff(ForkOffCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -35,7 +144,179 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Decode blocks and extrinsics, from the database or from hex.
+	Inspect(InspectCmd),
+
+	/// Build, sign and submit an extrinsic to a running node.
+	Tx(TxCmd),
+
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 }
+
+/// Decode a block or an extrinsic against the native runtime.
+#[derive(Debug, StructOpt)]
+pub struct InspectCmd {
+	#[structopt(subcommand)]
+	pub command: InspectSubCmd,
+
+	/// Print JSON instead of text.
+	#[structopt(long)]
+	pub json: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum InspectSubCmd {
+	/// Decode a block, with its events.
+	Block {
+		/// Hash or number of a block in the database, or a SCALE-encoded block in hex.
+		#[structopt(value_name = "HASH, NUMBER or BYTES")]
+		input: BlockAddress,
+	},
+	/// Decode an extrinsic, with its events.
+	Extrinsic {
+		/// `<block>:<index>` of an extrinsic in the database, or a SCALE-encoded extrinsic in
+		/// hex.
+		#[structopt(value_name = "BLOCK:INDEX or BYTES")]
+		input: ExtrinsicAddress,
+	},
+}
+
+impl CliConfiguration for InspectCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// Build, sign and submit an extrinsic to a running node.
+#[derive(Debug, StructOpt)]
+pub struct TxCmd {
+	#[structopt(subcommand)]
+	pub call: TxCall,
+
+	/// HTTP RPC endpoint of the node.
+	#[structopt(long, default_value = "http://localhost:9933")]
+	pub url: String,
+
+	/// Secret URI of the sr25519 signing key, e.g. `//Alice` or a mnemonic phrase.
+	#[structopt(long, conflicts_with = "keystore-path")]
+	pub suri: Option<String>,
+
+	/// Keystore holding the signing key under the `acco` key type.
+	#[structopt(long, parse(from_os_str), requires = "signer")]
+	pub keystore_path: Option<PathBuf>,
+
+	/// Password of the keystore.
+	#[structopt(long, requires = "keystore-path")]
+	pub password: Option<String>,
+
+	/// SS58 address of the signing key in the keystore.
+	#[structopt(long, requires = "keystore-path")]
+	pub signer: Option<String>,
+
+	/// Dispatch the call from the root origin through the sudo key.
+	#[structopt(long)]
+	pub sudo: bool,
+
+	/// Tip for the block author, e.g. `0.01 UNIT`.
+	#[structopt(long, default_value = "0")]
+	pub tip: String,
+
+	/// Number of blocks the extrinsic stays valid for after the last finalized block, or 0 to
+	/// make it immortal.
+	#[structopt(long, default_value = "64")]
+	pub mortality: u64,
+
+	/// Print the extrinsic and its fee instead of submitting it.
+	#[structopt(long)]
+	pub dry_run: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum TxCall {
+	/// Transfer funds to an account.
+	Transfer {
+		/// SS58 address of the recipient.
+		dest: String,
+		/// Amount in tokens, e.g. `12.5 UNIT`.
+		amount: String,
+	},
+	/// Open a room, reserving its deposit.
+	OpenRoom,
+	/// Join a room.
+	JoinRoom {
+		room: RoomId,
+	},
+	/// Leave a room.
+	LeaveRoom {
+		room: RoomId,
+	},
+	/// Add to the fee budget of a room you own, becoming its sponsor.
+	FundRoom {
+		room: RoomId,
+		/// Amount in tokens, e.g. `12.5 UNIT`.
+		amount: String,
+	},
+	/// Take funds out of the fee budget of a sponsored room.
+	WithdrawRoom {
+		room: RoomId,
+		/// Amount in tokens, e.g. `12.5 UNIT`.
+		amount: String,
+	},
+	/// Stop sponsoring a room and take back its budget.
+	StopSponsoring {
+		room: RoomId,
+	},
+	/// Remove a content anchor and take back its deposit.
+	RemoveAnchor {
+		/// Blake2-256 digest, or binary CID, in hex.
+		hash: String,
+	},
+	/// Upgrade the runtime through the sudo key.
+	SetCode {
+		/// Path of the Wasm runtime.
+		#[structopt(parse(from_os_str))]
+		wasm: PathBuf,
+	},
+	/// Any call, SCALE-encoded in hex.
+	Raw {
+		call: String,
+	},
+}
+
+/// Turn a chain spec written by `export-state` into a development chain spec.
+#[derive(Debug, StructOpt)]
+pub struct ForkOffCmd {
+	/// The raw chain spec written by `export-state`.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	/// Where to write the new chain spec, instead of the standard output.
+	#[structopt(long, short, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Seed of an authority of the fork, e.g. `Alice`. May be repeated.
+	#[structopt(long = "authority", default_value = "Alice")]
+	pub authorities: Vec<String>,
+
+	/// Seed or SS58 address of the sudo key of the fork.
+	#[structopt(long, default_value = "Alice")]
+	pub sudo: String,
+
+	/// Replace the runtime of the exported state with the one built into this node.
+	#[structopt(long)]
+	pub replace_code: bool,
+}
@@ -16,9 +16,10 @@
 // limitations under the License.
 
 use crate::{chain_spec, service};
-use crate::cli::{Cli, Subcommand};
+use crate::cli::{Cli, InspectSubCmd, Subcommand};
+use crate::inspect::Inspector;
 use sc_cli::{SubstrateCli, RuntimeVersion, Role, ChainSpec};
-use sc_service::PartialComponents;
+use sc_service::{ChainType, PartialComponents};
 use node_template_runtime::Block;
 
 impl SubstrateCli for Cli {
@@ -50,6 +51,10 @@ impl SubstrateCli for Cli {
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
+			"staging" => Box::new(chain_spec::staging_config()?),
+			id if id.starts_with("config:") => Box::new(chain_spec::config_file_spec(
+				std::path::Path::new(&id["config:".len()..]),
+			)?),
 			path => Box::new(chain_spec::ChainSpec::from_json_file(
 				std::path::PathBuf::from(path),
 			)?),
@@ -95,6 +100,7 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ForkOff(cmd)) => cmd.run().map_err(Into::into),
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -115,6 +121,26 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, backend), task_manager))
 			})
 		},
+		Some(Subcommand::Inspect(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				let inspector = Inspector::new(&*client);
+				let output = match &cmd.command {
+					InspectSubCmd::Block { input } => {
+						let block = inspector.block(input.clone())?;
+						if cmd.json { block.to_json().to_string() } else { block.to_string() }
+					},
+					InspectSubCmd::Extrinsic { input } => {
+						let xt = inspector.extrinsic(input.clone())?;
+						if cmd.json { xt.to_json().to_string() } else { xt.to_string() }
+					},
+				};
+				println!("{}", output);
+				Ok(())
+			})
+		},
+		Some(Subcommand::Tx(cmd)) => cmd.run().map_err(Into::into),
 		Some(Subcommand::Benchmark(cmd)) => {
 			if cfg!(feature = "runtime-benchmarks") {
 				let runner = cli.create_runner(cmd)?;
@@ -127,10 +153,36 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let sealing = cli.sealing;
+			let grandpa_observer = cli.grandpa_observer;
+			let backoff = cli.backoff.clone();
+			let keystore_credentials =
+				cli.remote_keystore.credentials().map_err(sc_cli::Error::Input)?;
 			runner.run_node_until_exit(|config| async move {
-				match config.role {
-					Role::Light => service::new_light(config),
-					_ => service::new_full(config),
+				match (config.role.clone(), sealing) {
+					(Role::Light, _) => service::new_light(config),
+					(_, Some(sealing)) => {
+						if config.chain_spec.chain_type() != ChainType::Development {
+							return Err(
+								"`--sealing` is only available for development chains".into()
+							)
+						}
+						service::new_dev(config, sealing)
+					},
+					(_, None) => {
+						let extensions = chain_spec::Extensions::try_get(&*config.chain_spec)
+							.cloned()
+							.unwrap_or_default();
+						let backoff = backoff.apply(extensions.aura.backoff);
+						if let Some(backoff) = &backoff {
+							backoff.validate()?;
+						}
+						service::new_full(config, service::FullOptions {
+							grandpa_observer,
+							backoff,
+							keystore_credentials,
+						})
+					},
 				}.map_err(sc_cli::Error::Service)
 			})
 		}
@@ -0,0 +1,229 @@
+//! Turning the state exported from a live chain into a development chain spec.
+//!
+//! `export-state` writes a raw chain spec whose genesis is the state of a block. To run it
+//! locally, its Aura and GRANDPA authorities are replaced with development keys, its sudo key
+//! with a key we control, and optionally its runtime with the one built into this node. Boot
+//! nodes and telemetry are dropped so the fork never talks to the live network.
+
+use codec::Encode;
+use serde_json::Value;
+use node_template_runtime::AccountId;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::{crypto::Ss58Codec, hashing::twox_128, hexdisplay::HexDisplay, sr25519};
+use sp_finality_grandpa::{
+	AuthorityId as GrandpaId, VersionedAuthorityList, GRANDPA_AUTHORITIES_KEY,
+};
+use crate::{chain_spec, cli::ForkOffCmd};
+
+/// The GRANDPA storage items tracking changes of the authority set.
+const GRANDPA_SET_CHANGES: &[&[u8]] = &[b"PendingChange", b"NextForced", b"Stalled", b"State"];
+
+/// What to replace in the exported state.
+pub struct ForkOff {
+	/// The new Aura and GRANDPA authorities.
+	pub authorities: Vec<(AuraId, GrandpaId)>,
+	/// The new sudo key.
+	pub sudo: AccountId,
+	/// The runtime code to use instead of that of the live chain.
+	pub code: Option<Vec<u8>>,
+}
+
+impl ForkOff {
+	/// Rewrite the raw chain spec `spec` into that of a development chain.
+	pub fn apply(&self, spec: &mut Value) -> Result<(), String> {
+		let top = spec.pointer_mut("/genesis/raw/top")
+			.and_then(Value::as_object_mut)
+			.ok_or("Expected a raw chain spec, as written by `export-state`")?;
+		// Scheduled and forced changes of the live authority set, and a stall or pause, would
+		// replace or halt the new authorities; the fork starts out live with no change pending.
+		for item in GRANDPA_SET_CHANGES {
+			top.remove(&hex(&storage_key(b"Grandpa", item)));
+		}
+		let mut set = |key: Vec<u8>, value: Vec<u8>| {
+			top.insert(hex(&key), Value::String(hex(&value)));
+		};
+
+		let aura: Vec<AuraId> = self.authorities.iter().map(|(aura, _)| aura.clone()).collect();
+		set(storage_key(b"Aura", b"Authorities"), aura.encode());
+
+		let grandpa: Vec<(GrandpaId, u64)> = self.authorities.iter()
+			.map(|(_, grandpa)| (grandpa.clone(), 1))
+			.collect();
+		set(GRANDPA_AUTHORITIES_KEY.to_vec(), VersionedAuthorityList::from(&grandpa).encode());
+		// The new authorities form the first set of the fork.
+		set(storage_key(b"Grandpa", b"CurrentSetId"), 0u64.encode());
+
+		set(storage_key(b"Sudo", b"Key"), self.sudo.encode());
+
+		if let Some(code) = &self.code {
+			set(sp_core::storage::well_known_keys::CODE.to_vec(), code.clone());
+			// Let migrations from the live runtime run on the first block.
+			top.remove(&hex(&storage_key(b"System", b"LastRuntimeUpgrade")));
+		}
+
+		let name = spec["name"].as_str().unwrap_or("Fork").to_string();
+		let id = spec["id"].as_str().unwrap_or("fork").to_string();
+		spec["name"] = Value::String(format!("{} Fork", name));
+		spec["id"] = Value::String(format!("{}_fork", id));
+		spec["chainType"] = Value::String("Development".into());
+		spec["bootNodes"] = Value::Array(Vec::new());
+		spec["telemetryEndpoints"] = Value::Null;
+		Ok(())
+	}
+}
+
+impl ForkOffCmd {
+	/// Read the exported chain spec, fork it off and write the result.
+	pub fn run(&self) -> Result<(), String> {
+		let input = std::fs::read(&self.input)
+			.map_err(|e| format!("Unable to read {}: {}", self.input.display(), e))?;
+		let mut spec: Value = serde_json::from_slice(&input)
+			.map_err(|e| format!("Invalid chain spec {}: {}", self.input.display(), e))?;
+
+		let code = if self.replace_code {
+			let wasm = node_template_runtime::WASM_BINARY
+				.ok_or("Development wasm binary not available")?;
+			Some(wasm.to_vec())
+		} else {
+			None
+		};
+		let sudo = match AccountId::from_ss58check(&self.sudo) {
+			Ok(account) => account,
+			Err(_) => chain_spec::get_account_id_from_seed::<sr25519::Public>(&self.sudo),
+		};
+		ForkOff {
+			authorities: self.authorities.iter()
+				.map(|seed| chain_spec::authority_keys_from_seed(seed))
+				.collect(),
+			sudo,
+			code,
+		}.apply(&mut spec)?;
+
+		let output = serde_json::to_string_pretty(&spec)
+			.map_err(|e| format!("Unable to serialize the chain spec: {}", e))?;
+		match &self.output {
+			Some(path) => std::fs::write(path, output)
+				.map_err(|e| format!("Unable to write {}: {}", path.display(), e)),
+			None => {
+				println!("{}", output);
+				Ok(())
+			},
+		}
+	}
+}
+
+/// The key of the storage value `pallet::item`.
+fn storage_key(pallet: &[u8], item: &[u8]) -> Vec<u8> {
+	[twox_128(pallet), twox_128(item)].concat()
+}
+
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", HexDisplay::from(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Decode;
+	use crate::chain_spec::{authority_keys_from_seed, get_account_id_from_seed};
+
+	fn exported() -> Value {
+		let mut top = serde_json::Map::new();
+		top.insert(hex(&storage_key(b"Sudo", b"Key")), Value::String(hex(&[7; 32])));
+		top.insert(
+			hex(&storage_key(b"System", b"LastRuntimeUpgrade")),
+			Value::String("0x00".into()),
+		);
+		top.insert(hex(b":code"), Value::String("0x0102".into()));
+		for item in GRANDPA_SET_CHANGES {
+			top.insert(hex(&storage_key(b"Grandpa", item)), Value::String("0x01".into()));
+		}
+		serde_json::json!({
+			"name": "Listen",
+			"id": "listen",
+			"chainType": "Live",
+			"bootNodes": ["/dns/boot.listen.io/tcp/30333"],
+			"telemetryEndpoints": [["wss://telemetry.polkadot.io/submit/", 0]],
+			"genesis": { "raw": { "top": top, "childrenDefault": {} } },
+		})
+	}
+
+	fn stored(spec: &Value, key: &[u8]) -> Option<Vec<u8>> {
+		spec["genesis"]["raw"]["top"][hex(key)].as_str()
+			.map(|value| sp_core::bytes::from_hex(value).unwrap())
+	}
+
+	#[test]
+	fn replaces_authorities_and_sudo() {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let fork = ForkOff {
+			authorities: vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+			sudo: alice.clone(),
+			code: None,
+		};
+		let mut spec = exported();
+		fork.apply(&mut spec).unwrap();
+
+		let aura = stored(&spec, &storage_key(b"Aura", b"Authorities")).unwrap();
+		assert_eq!(
+			Vec::<AuraId>::decode(&mut &aura[..]).unwrap(),
+			vec![authority_keys_from_seed("Alice").0, authority_keys_from_seed("Bob").0],
+		);
+		let grandpa = stored(&spec, GRANDPA_AUTHORITIES_KEY).unwrap();
+		let grandpa = VersionedAuthorityList::decode(&mut &grandpa[..]).unwrap();
+		assert_eq!(grandpa.into_inner().len(), 2);
+		let sudo = stored(&spec, &storage_key(b"Sudo", b"Key")).unwrap();
+		assert_eq!(AccountId::decode(&mut &sudo[..]).unwrap(), alice);
+
+		// The live runtime is kept.
+		assert_eq!(stored(&spec, b":code"), Some(vec![1, 2]));
+		assert!(stored(&spec, &storage_key(b"System", b"LastRuntimeUpgrade")).is_some());
+
+		assert_eq!(spec["id"], "listen_fork");
+		assert_eq!(spec["chainType"], "Development");
+		assert_eq!(spec["bootNodes"], serde_json::json!([]));
+		assert!(spec["telemetryEndpoints"].is_null());
+	}
+
+	#[test]
+	fn drops_pending_grandpa_set_changes() {
+		let fork = ForkOff {
+			authorities: vec![authority_keys_from_seed("Alice")],
+			sudo: get_account_id_from_seed::<sr25519::Public>("Alice"),
+			code: None,
+		};
+		let mut spec = exported();
+		fork.apply(&mut spec).unwrap();
+
+		for item in GRANDPA_SET_CHANGES {
+			assert_eq!(stored(&spec, &storage_key(b"Grandpa", item)), None);
+		}
+		let set_id = stored(&spec, &storage_key(b"Grandpa", b"CurrentSetId")).unwrap();
+		assert_eq!(u64::decode(&mut &set_id[..]).unwrap(), 0);
+	}
+
+	#[test]
+	fn replaces_code() {
+		let fork = ForkOff {
+			authorities: vec![authority_keys_from_seed("Alice")],
+			sudo: get_account_id_from_seed::<sr25519::Public>("Alice"),
+			code: Some(vec![3, 4]),
+		};
+		let mut spec = exported();
+		fork.apply(&mut spec).unwrap();
+
+		assert_eq!(stored(&spec, b":code"), Some(vec![3, 4]));
+		assert_eq!(stored(&spec, &storage_key(b"System", b"LastRuntimeUpgrade")), None);
+	}
+
+	#[test]
+	fn rejects_non_raw_specs() {
+		let fork = ForkOff {
+			authorities: vec![authority_keys_from_seed("Alice")],
+			sudo: get_account_id_from_seed::<sr25519::Public>("Alice"),
+			code: None,
+		};
+		let mut spec = serde_json::json!({ "genesis": { "runtime": {} } });
+		assert!(fork.apply(&mut spec).is_err());
+	}
+}
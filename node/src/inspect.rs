@@ -0,0 +1,366 @@
+//! Decoding of blocks and extrinsics against the native runtime, for support and debugging.
+//!
+//! Blocks are given by hash, by number or as SCALE-encoded hex; extrinsics as
+//! `<block>:<index>` or as SCALE-encoded hex. Blocks and extrinsics read from the database are
+//! printed with the events they emitted; decoded hex has not been applied and has none.
+
+use std::{fmt, str::FromStr};
+use codec::{Compact, Decode, Encode};
+use frame_system::{EventRecord, Phase};
+use node_template_runtime::{
+	Address, Balance, Block, BlockNumber, Event, Hash, Index, UncheckedExtrinsic,
+};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::Ss58Codec, hashing::twox_128, storage::StorageKey};
+use sp_runtime::{
+	generic::{BlockId, Era},
+	traits::{BlakeTwo256, Block as BlockT, Hash as HashT, Header as HeaderT},
+	MultiAddress,
+};
+
+/// A block given on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockAddress {
+	/// The block with this hash in the database.
+	Hash(Hash),
+	/// The best chain's block with this number in the database.
+	Number(BlockNumber),
+	/// A SCALE-encoded block.
+	Bytes(Vec<u8>),
+}
+
+impl FromStr for BlockAddress {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Ok(number) = s.parse() {
+			return Ok(BlockAddress::Number(number))
+		}
+		let bytes = sp_core::bytes::from_hex(s)
+			.map_err(|e| format!("Invalid block `{}`, expected a hash, number or hex: {}", s, e))?;
+		Ok(if bytes.len() == Hash::len_bytes() {
+			BlockAddress::Hash(Hash::from_slice(&bytes))
+		} else {
+			BlockAddress::Bytes(bytes)
+		})
+	}
+}
+
+/// An extrinsic given on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtrinsicAddress {
+	/// The extrinsic at this index of a block.
+	Block(BlockAddress, usize),
+	/// A SCALE-encoded extrinsic.
+	Bytes(Vec<u8>),
+}
+
+impl FromStr for ExtrinsicAddress {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Some(colon) = s.rfind(':') {
+			let (block, index) = (&s[..colon], &s[colon + 1..]);
+			let index = index.parse()
+				.map_err(|e| format!("Invalid extrinsic index `{}`: {}", index, e))?;
+			return Ok(ExtrinsicAddress::Block(block.parse()?, index))
+		}
+		sp_core::bytes::from_hex(s)
+			.map(ExtrinsicAddress::Bytes)
+			.map_err(|e| format!(
+				"Invalid extrinsic `{}`, expected `<block>:<index>` or hex: {}", s, e,
+			))
+	}
+}
+
+/// A decoded extrinsic.
+pub struct Inspected {
+	/// Position in its block, if read from one.
+	pub index: Option<usize>,
+	/// Hash of the encoded extrinsic.
+	pub hash: Hash,
+	/// The decoded extrinsic.
+	pub extrinsic: UncheckedExtrinsic,
+	/// Events emitted while applying the extrinsic.
+	pub events: Vec<Event>,
+}
+
+impl Inspected {
+	pub(crate) fn new(index: Option<usize>, extrinsic: UncheckedExtrinsic, events: Vec<Event>) -> Self {
+		let hash = BlakeTwo256::hash(&extrinsic.encode());
+		Inspected { index, hash, extrinsic, events }
+	}
+
+	/// The signer, in SS58 format when it is an account id.
+	pub fn signer(&self) -> Option<String> {
+		self.extrinsic.signature.as_ref().map(|(address, _, _)| format_address(address))
+	}
+
+	/// Mortality of a signed extrinsic.
+	pub fn era(&self) -> Option<Era> {
+		// The extensions' fields are private, but they encode as their content.
+		self.extrinsic.signature.as_ref()
+			.and_then(|(_, _, extra)| Era::decode(&mut &extra.3.encode()[..]).ok())
+	}
+
+	/// Nonce of a signed extrinsic.
+	pub fn nonce(&self) -> Option<Index> {
+		self.extrinsic.signature.as_ref()
+			.and_then(|(_, _, extra)| Compact::<Index>::decode(&mut &extra.4.encode()[..]).ok())
+			.map(|nonce| nonce.0)
+	}
+
+	/// Tip of a signed extrinsic.
+	pub fn tip(&self) -> Option<Balance> {
+		self.extrinsic.signature.as_ref()
+			.and_then(|(_, _, extra)| Compact::<Balance>::decode(&mut &extra.7.encode()[..]).ok())
+			.map(|tip| tip.0)
+	}
+
+	/// JSON representation, with calls and events in their debug format.
+	pub fn to_json(&self) -> serde_json::Value {
+		serde_json::json!({
+			"index": self.index,
+			"hash": format!("{:?}", self.hash),
+			"signer": self.signer(),
+			"call": format!("{:?}", self.extrinsic.function),
+			"era": self.era().map(|era| format_era(&era)),
+			"nonce": self.nonce(),
+			// Balances do not fit in JSON numbers.
+			"tip": self.tip().map(|tip| tip.to_string()),
+			"events": self.events.iter().map(|e| format!("{:?}", e)).collect::<Vec<_>>(),
+		})
+	}
+}
+
+impl fmt::Display for Inspected {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.index {
+			Some(index) => writeln!(f, "Extrinsic #{} {:?}", index, self.hash)?,
+			None => writeln!(f, "Extrinsic {:?}", self.hash)?,
+		}
+		match self.signer() {
+			Some(signer) => writeln!(f, "  signer: {}", signer)?,
+			None => writeln!(f, "  unsigned")?,
+		}
+		writeln!(f, "  call:   {:?}", self.extrinsic.function)?;
+		if let (Some(era), Some(nonce), Some(tip)) = (self.era(), self.nonce(), self.tip()) {
+			writeln!(f, "  era:    {}", format_era(&era))?;
+			writeln!(f, "  nonce:  {}", nonce)?;
+			writeln!(f, "  tip:    {}", tip)?;
+		}
+		if !self.events.is_empty() {
+			writeln!(f, "  events:")?;
+			for event in &self.events {
+				writeln!(f, "    {:?}", event)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A decoded block.
+pub struct InspectedBlock {
+	/// The block's header.
+	pub header: <Block as BlockT>::Header,
+	/// The block's extrinsics.
+	pub extrinsics: Vec<Inspected>,
+	/// Events emitted outside of extrinsics, e.g. by `on_initialize`.
+	pub events: Vec<Event>,
+}
+
+impl InspectedBlock {
+	/// JSON representation, with calls and events in their debug format.
+	pub fn to_json(&self) -> serde_json::Value {
+		serde_json::json!({
+			"number": self.header.number,
+			"hash": format!("{:?}", self.header.hash()),
+			"parent_hash": format!("{:?}", self.header.parent_hash),
+			"extrinsics": self.extrinsics.iter().map(Inspected::to_json).collect::<Vec<_>>(),
+			"events": self.events.iter().map(|e| format!("{:?}", e)).collect::<Vec<_>>(),
+		})
+	}
+}
+
+impl fmt::Display for InspectedBlock {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "Block #{} {:?}", self.header.number, self.header.hash())?;
+		writeln!(f, "  parent: {:?}", self.header.parent_hash)?;
+		for extrinsic in &self.extrinsics {
+			writeln!(f)?;
+			write!(f, "{}", extrinsic)?;
+		}
+		if !self.events.is_empty() {
+			writeln!(f)?;
+			writeln!(f, "Block events:")?;
+			for event in &self.events {
+				writeln!(f, "  {:?}", event)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Reads and decodes blocks and extrinsics of a client's database.
+pub struct Inspector<'a, C, B> {
+	client: &'a C,
+	_backend: std::marker::PhantomData<B>,
+}
+
+impl<'a, C, B> Inspector<'a, C, B> where
+	C: HeaderBackend<node_template_runtime::opaque::Block>,
+	C: BlockBackend<node_template_runtime::opaque::Block>,
+	C: StorageProvider<node_template_runtime::opaque::Block, B>,
+	B: Backend<node_template_runtime::opaque::Block>,
+{
+	/// An inspector reading from `client`.
+	pub fn new(client: &'a C) -> Self {
+		Inspector { client, _backend: Default::default() }
+	}
+
+	/// Decode a block, with its events if it is read from the database.
+	pub fn block(&self, address: BlockAddress) -> Result<InspectedBlock, String> {
+		let hash = match address {
+			BlockAddress::Hash(hash) => hash,
+			BlockAddress::Number(number) => self.client.hash(number)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| format!("Block #{} is not in the database", number))?,
+			BlockAddress::Bytes(bytes) => {
+				let block = Block::decode(&mut &bytes[..])
+					.map_err(|e| format!("Unable to decode the block: {}", e))?;
+				return Ok(InspectedBlock {
+					header: block.header,
+					extrinsics: block.extrinsics.into_iter().enumerate()
+						.map(|(index, xt)| Inspected::new(Some(index), xt, Vec::new()))
+						.collect(),
+					events: Vec::new(),
+				})
+			},
+		};
+
+		let at = BlockId::Hash(hash);
+		let header = self.client.header(at).map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Block {:?} is not in the database", hash))?;
+		let body = self.client.block_body(&at).map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("The body of block {:?} is not in the database", hash))?;
+		let mut events = self.events(&at)?;
+
+		let extrinsics = body.into_iter().enumerate().map(|(index, xt)| {
+			let extrinsic = UncheckedExtrinsic::decode(&mut &xt.encode()[..])
+				.map_err(|e| format!("Unable to decode extrinsic #{}: {}", index, e))?;
+			Ok(Inspected::new(Some(index), extrinsic, take_events(&mut events, index)))
+		}).collect::<Result<_, String>>()?;
+
+		Ok(InspectedBlock {
+			header,
+			extrinsics,
+			events: events.into_iter().map(|record| record.event).collect(),
+		})
+	}
+
+	/// Decode an extrinsic, with its events if it is read from the database.
+	pub fn extrinsic(&self, address: ExtrinsicAddress) -> Result<Inspected, String> {
+		match address {
+			ExtrinsicAddress::Block(block, index) => {
+				let block = self.block(block)?;
+				let count = block.extrinsics.len();
+				block.extrinsics.into_iter().nth(index).ok_or_else(|| format!(
+					"Block #{} has {} extrinsics, no #{}", block.header.number, count, index,
+				))
+			},
+			ExtrinsicAddress::Bytes(bytes) => UncheckedExtrinsic::decode(&mut &bytes[..])
+				.map(|xt| Inspected::new(None, xt, Vec::new()))
+				.map_err(|e| format!("Unable to decode the extrinsic: {}", e)),
+		}
+	}
+
+	/// All events of the block `at`.
+	fn events(
+		&self,
+		at: &BlockId<node_template_runtime::opaque::Block>,
+	) -> Result<Vec<EventRecord<Event, Hash>>, String> {
+		let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+		match self.client.storage(at, &key).map_err(|e| e.to_string())? {
+			Some(data) => Decode::decode(&mut &data.0[..])
+				.map_err(|e| format!("Unable to decode the events: {}", e)),
+			None => Ok(Vec::new()),
+		}
+	}
+}
+
+/// Remove the events of the extrinsic at `index` from `events`.
+fn take_events(events: &mut Vec<EventRecord<Event, Hash>>, index: usize) -> Vec<Event> {
+	let (taken, rest) = std::mem::take(events).into_iter()
+		.partition::<Vec<_>, _>(|record| record.phase == Phase::ApplyExtrinsic(index as u32));
+	*events = rest;
+	taken.into_iter().map(|record| record.event).collect()
+}
+
+fn format_address(address: &Address) -> String {
+	match address {
+		MultiAddress::Id(account) => account.to_ss58check(),
+		address => format!("{:?}", address),
+	}
+}
+
+fn format_era(era: &Era) -> String {
+	match era {
+		Era::Immortal => "immortal".into(),
+		Era::Mortal(period, phase) => format!("mortal, period {}, phase {}", period, phase),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use node_template_runtime::{BalancesCall, Call};
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::MultiSignature;
+
+	#[test]
+	fn parses_block_addresses() {
+		assert_eq!("42".parse(), Ok(BlockAddress::Number(42)));
+		let hash = format!("0x{}", "ab".repeat(32));
+		assert_eq!(hash.parse(), Ok(BlockAddress::Hash(Hash::repeat_byte(0xab))));
+		assert_eq!("0x0102".parse(), Ok(BlockAddress::Bytes(vec![1, 2])));
+		assert!("latest".parse::<BlockAddress>().is_err());
+	}
+
+	#[test]
+	fn parses_extrinsic_addresses() {
+		assert_eq!("42:1".parse(), Ok(ExtrinsicAddress::Block(BlockAddress::Number(42), 1)));
+		assert_eq!("0x0102".parse(), Ok(ExtrinsicAddress::Bytes(vec![1, 2])));
+		assert!("42:x".parse::<ExtrinsicAddress>().is_err());
+	}
+
+	#[test]
+	fn decodes_signed_extras() {
+		let alice = AccountKeyring::Alice;
+		let call = Call::Balances(
+			BalancesCall::transfer(AccountKeyring::Bob.to_account_id().into(), 5)
+		);
+		let extra = (
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(Era::mortal(64, 10)),
+			frame_system::CheckNonce::from(7),
+			frame_system::CheckWeight::new(),
+			pallet_rate_limit::CheckRateLimit::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(3).into(),
+		);
+		// The signature is not checked when decoding.
+		let signature = MultiSignature::from(alice.sign(&[]));
+		let xt = UncheckedExtrinsic::new_signed(
+			call, alice.to_account_id().into(), signature, extra,
+		);
+
+		let inspected = Inspected::new(None, xt, Vec::new());
+		assert_eq!(inspected.signer(), Some(alice.to_account_id().to_ss58check()));
+		assert_eq!(inspected.era(), Some(Era::mortal(64, 10)));
+		assert_eq!(inspected.nonce(), Some(7));
+		assert_eq!(inspected.tip(), Some(3));
+		assert_eq!(inspected.to_json()["tip"], "3");
+	}
+}
@@ -1,3 +1,10 @@
+pub mod backoff;
 pub mod chain_spec;
+pub mod cli;
+pub mod inspect;
+pub mod metrics;
 pub mod service;
 pub mod rpc;
+pub mod sealing;
+pub mod tx;
+pub mod voting_rules;
@@ -1,12 +1,19 @@
 //! Substrate Node Template CLI library.
 #![warn(missing_docs)]
 
+mod backoff;
 mod chain_spec;
 #[macro_use]
 mod service;
 mod cli;
 mod command;
+mod fork_off;
+mod inspect;
+mod metrics;
 mod rpc;
+mod sealing;
+mod tx;
+mod voting_rules;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
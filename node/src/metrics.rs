@@ -0,0 +1,222 @@
+//! Chain-level Prometheus metrics, registered on the node's registry next to Substrate's own.
+//!
+//! - `listen_finality_lag`: blocks between the best and the last finalized block;
+//! - `listen_block_fees` / `listen_fees_total`: fees of the signed extrinsics of finalized blocks,
+//!   whether the signer or a room budget paid them, as `SponsorshipApi::query_info` estimates them
+//!   against the parent block: before refunds of unused weight, and zero for free calls;
+//! - `listen_block_tips` / `listen_tips_total`: tips of the signed extrinsics of finalized blocks;
+//! - `listen_pool_ready{pallet}`: ready transactions in the pool, by pallet of their call;
+//! - `listen_rooms` / `listen_room_members`: open rooms, and members of all open rooms;
+//! - `listen_sponsored_rooms` / `listen_anchored_rooms`: rooms with a fee sponsor and rooms with
+//!   anchored content.
+//!
+//! Counts are read from counters the pallets keep in storage, so updating them costs a few reads
+//! per finalized block however many rooms there are.
+
+use std::sync::Arc;
+use codec::{Compact, Decode, Encode};
+use frame_support::{storage::StorageValue, traits::GetCallMetadata};
+use futures::{future, Future, StreamExt};
+use node_template_runtime::{opaque::Block, AccountId, Balance, UncheckedExtrinsic};
+use pallet_sponsorship_runtime_api::SponsorshipApi;
+use sc_client_api::{Backend, BlockBackend, BlockchainEvents, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use sp_transaction_pool::{InPoolTransaction, TransactionPool};
+use substrate_prometheus_endpoint::{
+	register, Counter, Gauge, GaugeVec, Opts, PrometheusError, Registry, F64, U64,
+};
+
+struct Metrics {
+	finality_lag: Gauge<U64>,
+	block_fees: Gauge<F64>,
+	fees_total: Counter<F64>,
+	block_tips: Gauge<F64>,
+	tips_total: Counter<F64>,
+	pool_ready: GaugeVec<U64>,
+	rooms: Gauge<U64>,
+	room_members: Gauge<U64>,
+	sponsored_rooms: Gauge<U64>,
+	anchored_rooms: Gauge<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Metrics {
+			finality_lag: register(
+				Gauge::new("listen_finality_lag", "Blocks between the best and the finalized block.")?,
+				registry,
+			)?,
+			block_fees: register(
+				Gauge::new("listen_block_fees", "Fees paid in the last finalized block.")?,
+				registry,
+			)?,
+			fees_total: register(
+				Counter::new("listen_fees_total", "Fees paid in finalized blocks.")?,
+				registry,
+			)?,
+			block_tips: register(
+				Gauge::new("listen_block_tips", "Tips paid in the last finalized block.")?,
+				registry,
+			)?,
+			tips_total: register(
+				Counter::new("listen_tips_total", "Tips paid in finalized blocks.")?,
+				registry,
+			)?,
+			pool_ready: register(
+				GaugeVec::new(
+					Opts::new("listen_pool_ready", "Ready transactions in the pool, by pallet."),
+					&["pallet"],
+				)?,
+				registry,
+			)?,
+			rooms: register(Gauge::new("listen_rooms", "Open rooms.")?, registry)?,
+			room_members: register(
+				Gauge::new("listen_room_members", "Members of all open rooms.")?,
+				registry,
+			)?,
+			sponsored_rooms: register(
+				Gauge::new("listen_sponsored_rooms", "Rooms with a fee sponsor.")?,
+				registry,
+			)?,
+			anchored_rooms: register(
+				Gauge::new("listen_anchored_rooms", "Rooms with anchored content.")?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// What a notification is about.
+enum Event {
+	Imported,
+	Finalized(<Block as BlockT>::Hash),
+}
+
+/// Keep the metrics up to date as blocks are imported and finalized.
+pub fn run<C, B, P>(
+	client: Arc<C>,
+	pool: Arc<P>,
+	registry: &Registry,
+) -> Result<impl Future<Output = ()>, PrometheusError> where
+	C: BlockchainEvents<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+	C: StorageProvider<Block, B> + ProvideRuntimeApi<Block>,
+	C::Api: SponsorshipApi<Block, AccountId, Balance>,
+	B: Backend<Block>,
+	P: TransactionPool<Block = Block>,
+{
+	let metrics = Metrics::register(registry)?;
+	let imported = client.import_notification_stream().map(|_| Event::Imported);
+	let finalized = client.finality_notification_stream()
+		.map(|notification| Event::Finalized(notification.hash));
+
+	Ok(futures::stream::select(imported, finalized).for_each(move |event| {
+		let info = client.info();
+		metrics.finality_lag.set(info.best_number.saturating_sub(info.finalized_number).into());
+
+		match event {
+			Event::Imported => update_pool(&metrics, &*pool),
+			Event::Finalized(hash) => {
+				if let Err(e) = update_finalized(&metrics, &*client, hash) {
+					log::warn!(target: "metrics", "Error reading finalized block {}: {}", hash, e);
+				}
+			},
+		}
+		future::ready(())
+	}))
+}
+
+fn update_pool<P: TransactionPool<Block = Block>>(metrics: &Metrics, pool: &P) {
+	metrics.pool_ready.reset();
+	for tx in pool.ready() {
+		let pallet = match decode(tx.data()) {
+			Some(uxt) => uxt.function.get_call_metadata().pallet_name,
+			None => {
+				log::warn!(
+					target: "metrics", "Undecodable transaction {:?} in the pool", tx.hash(),
+				);
+				"unknown"
+			},
+		};
+		metrics.pool_ready.with_label_values(&[pallet]).inc();
+	}
+}
+
+fn update_finalized<C, B>(
+	metrics: &Metrics,
+	client: &C,
+	hash: <Block as BlockT>::Hash,
+) -> Result<(), String> where
+	C: HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, B>,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: SponsorshipApi<Block, AccountId, Balance>,
+	B: Backend<Block>,
+{
+	let (fees, tips) = fees_and_tips(client, hash)?;
+	metrics.block_fees.set(fees as f64);
+	metrics.fees_total.inc_by(fees as f64);
+	metrics.block_tips.set(tips as f64);
+	metrics.tips_total.inc_by(tips as f64);
+
+	let at = BlockId::Hash(hash);
+	let count = |key: [u8; 32]| read_value::<_, _, u32>(client, &at, &key).map(u64::from);
+	metrics.rooms.set(count(pallet_rooms::RoomCount::hashed_key())?);
+	metrics.room_members.set(count(pallet_rooms::MemberCount::hashed_key())?);
+	metrics.sponsored_rooms.set(count(pallet_sponsorship::SponsoredRooms::hashed_key())?);
+	metrics.anchored_rooms.set(count(pallet_content_anchor::AnchoredRooms::hashed_key())?);
+	Ok(())
+}
+
+/// The fees and tips of the signed extrinsics of block `hash`.
+///
+/// Fees are queried against the parent block, whose state the extrinsics were charged in.
+fn fees_and_tips<C>(client: &C, hash: <Block as BlockT>::Hash) -> Result<(Balance, Balance), String>
+where
+	C: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>,
+	C::Api: SponsorshipApi<Block, AccountId, Balance>,
+{
+	let at = BlockId::Hash(hash);
+	let parent = client.header(at).map_err(|e| e.to_string())?
+		.map(|header| BlockId::Hash(*header.parent_hash()))
+		.ok_or_else(|| "Unknown block".to_string())?;
+	let extrinsics = client.block_body(&at).map_err(|e| e.to_string())?
+		.ok_or_else(|| "Missing block body".to_string())?;
+
+	let api = client.runtime_api();
+	let (mut fees, mut tips) = (0 as Balance, 0 as Balance);
+	for xt in extrinsics {
+		let uxt = decode(&xt).ok_or_else(|| "Undecodable extrinsic".to_string())?;
+		let tip = match &uxt.signature {
+			// `ChargeTransactionPayment` encodes as its compact tip and has no getter for it.
+			Some((_, _, extra)) => Compact::<Balance>::decode(&mut &extra.7.encode()[..])
+				.map_err(|e| format!("Undecodable tip: {}", e))?
+				.0,
+			// Unsigned extrinsics pay no fees.
+			None => continue,
+		};
+		let len = uxt.encoded_size() as u32;
+		let info = api.query_info(&parent, xt, len).map_err(|e| e.to_string())?;
+		fees = fees.saturating_add(info.info.partial_fee);
+		tips = tips.saturating_add(tip);
+	}
+	Ok((fees, tips))
+}
+
+/// Decode an opaque extrinsic of this runtime.
+fn decode(xt: &<Block as BlockT>::Extrinsic) -> Option<UncheckedExtrinsic> {
+	UncheckedExtrinsic::decode(&mut &xt.encode()[..]).ok()
+}
+
+/// The storage value at `key` at `at`, or its default if it is not set.
+fn read_value<C: StorageProvider<Block, B>, B: Backend<Block>, T: Decode + Default>(
+	client: &C,
+	at: &BlockId<Block>,
+	key: &[u8],
+) -> Result<T, String> {
+	match client.storage(at, &StorageKey(key.to_vec())).map_err(|e| e.to_string())? {
+		Some(data) => T::decode(&mut &data.0[..]).map_err(|e| e.to_string()),
+		None => Ok(T::default()),
+	}
+}
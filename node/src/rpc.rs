@@ -7,13 +7,34 @@
 
 use std::sync::Arc;
 
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use node_template_runtime::{
+	node_primitives::balance::Unit, opaque::Block, AccountId, Balance, Hash, Index,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_block_builder::BlockBuilder;
 pub use sc_rpc_api::DenyUnsafe;
 use sp_transaction_pool::TransactionPool;
+use sc_consensus_manual_seal::EngineCommand;
+use sc_client_api::light::{Fetcher, RemoteBlockchain};
+use futures::channel::mpsc::Sender;
 
+mod light;
+mod units;
+
+/// Light client extra dependencies.
+pub struct LightDeps<C, F, P> {
+	/// The client instance to use.
+	pub client: Arc<C>,
+	/// Transaction pool instance.
+	pub pool: Arc<P>,
+	/// Remote access to the blockchain (async).
+	pub remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+	/// Fetcher instance.
+	pub fetcher: Arc<F>,
+	/// The unit balances are formatted in.
+	pub unit: Unit,
+}
 
 /// Full client dependencies.
 pub struct FullDeps<C, P> {
@@ -23,6 +44,10 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Sink for the commands of the manual seal RPC, if blocks are sealed manually.
+	pub command_sink: Option<Sender<EngineCommand<Hash>>>,
+	/// The unit balances are formatted in.
+	pub unit: Unit,
 }
 
 /// Instantiate all full RPC extensions.
@@ -34,17 +59,25 @@ pub fn create_full<C, P>(
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_sponsorship_rpc::SponsorshipRuntimeApi<Block, AccountId, Balance>,
+	C::Api: pallet_rooms_rpc::ListenRuntimeApi<Block, AccountId, Balance>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use pallet_sponsorship_rpc::{Sponsorship, SponsorshipApi};
+	use pallet_rooms_rpc::{Listen, ListenApi};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
+	use units::{Units, UnitsApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
 		pool,
 		deny_unsafe,
+		command_sink,
+		unit,
 	} = deps;
 
 	io.extend_with(
@@ -55,6 +88,26 @@ pub fn create_full<C, P>(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
 
+	io.extend_with(
+		SponsorshipApi::to_delegate(Sponsorship::new(client.clone()))
+	);
+
+	io.extend_with(
+		ListenApi::to_delegate(Listen::new(client.clone()))
+	);
+
+	io.extend_with(
+		UnitsApi::to_delegate(Units::new(unit))
+	);
+
+	if let Some(command_sink) = command_sink {
+		io.extend_with(
+			// We provide the rpc handler with the sending end of the channel to allow the rpc
+			// send EngineCommands to the background block authorship task.
+			ManualSealApi::to_delegate(ManualSeal::new(command_sink))
+		);
+	}
+
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
 	// to call into the runtime.
@@ -62,3 +115,46 @@ pub fn create_full<C, P>(
 
 	io
 }
+
+/// Instantiate all light RPC extensions.
+///
+/// Methods that need the runtime state are served through remote calls to full nodes.
+pub fn create_light<C, P, F>(
+	deps: LightDeps<C, F, P>,
+) -> jsonrpc_core::IoHandler<sc_rpc::Metadata> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+	P: TransactionPool + 'static,
+{
+	use substrate_frame_rpc_system::{LightSystem, SystemApi};
+	use light::{LightSponsorshipApi, LightTransactionPaymentApi, RemoteRuntime};
+	use units::{Units, UnitsApi};
+
+	let mut io = jsonrpc_core::IoHandler::default();
+	let LightDeps {
+		client,
+		pool,
+		remote_blockchain,
+		fetcher,
+		unit,
+	} = deps;
+
+	io.extend_with(
+		SystemApi::<Hash, AccountId, Index>::to_delegate(
+			LightSystem::new(client.clone(), remote_blockchain.clone(), fetcher.clone(), pool)
+		)
+	);
+
+	let remote_runtime = RemoteRuntime::new(client, remote_blockchain, fetcher);
+	io.extend_with(
+		LightTransactionPaymentApi::to_delegate(remote_runtime.clone())
+	);
+	io.extend_with(
+		LightSponsorshipApi::to_delegate(remote_runtime)
+	);
+	io.extend_with(
+		UnitsApi::to_delegate(Units::new(unit))
+	);
+
+	io
+}
@@ -0,0 +1,181 @@
+//! Runtime API based RPC methods served by light clients through remote calls to full nodes.
+//!
+//! The methods have the names and results of their full node counterparts in
+//! `pallet-transaction-payment-rpc` and `pallet-sponsorship-rpc`, whose implementations need a
+//! local runtime state.
+
+use std::sync::Arc;
+use codec::{Decode, Encode};
+use futures::{FutureExt, TryFutureExt};
+use jsonrpc_core::{futures::Future as _, BoxFuture, Error as RpcError, ErrorCode};
+use jsonrpc_derive::rpc;
+use node_template_runtime::{opaque::Block, AccountId, Balance, Hash};
+use pallet_sponsorship_runtime_api::SponsoredDispatchInfo;
+use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
+use sc_client_api::light::{future_header, Fetcher, RemoteBlockchain, RemoteCallRequest};
+use sp_blockchain::{Error as ClientError, HeaderBackend};
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// The extrinsic could not be decoded.
+const DECODE_ERROR: i64 = 1;
+/// The remote call failed.
+const RUNTIME_ERROR: i64 = 2;
+
+/// Light client variant of `pallet_transaction_payment_rpc::TransactionPaymentApi`.
+#[rpc]
+pub trait LightTransactionPaymentApi {
+	/// Weight, class and fee of an extrinsic.
+	#[rpc(name = "payment_queryInfo")]
+	fn query_info(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<RuntimeDispatchInfo<Balance>>;
+
+	/// Breakdown of the fee of an extrinsic.
+	#[rpc(name = "payment_queryFeeDetails")]
+	fn query_fee_details(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<FeeDetails<NumberOrHex>>;
+}
+
+/// Light client variant of `pallet_sponsorship_rpc::SponsorshipApi`.
+#[rpc]
+pub trait LightSponsorshipApi {
+	/// Weight, class and fee of an extrinsic, and who pays the fee.
+	#[rpc(name = "sponsorship_queryInfo")]
+	fn query_info(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<SponsoredDispatchInfo<AccountId, Balance>>;
+}
+
+/// Calls the runtime APIs of full nodes on behalf of a light client.
+pub struct RemoteRuntime<C, F> {
+	client: Arc<C>,
+	remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+	fetcher: Arc<F>,
+}
+
+impl<C, F> Clone for RemoteRuntime<C, F> {
+	fn clone(&self) -> Self {
+		RemoteRuntime {
+			client: self.client.clone(),
+			remote_blockchain: self.remote_blockchain.clone(),
+			fetcher: self.fetcher.clone(),
+		}
+	}
+}
+
+impl<C, F> RemoteRuntime<C, F> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+{
+	/// A remote runtime reached through `fetcher`.
+	pub fn new(
+		client: Arc<C>,
+		remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+		fetcher: Arc<F>,
+	) -> Self {
+		RemoteRuntime { client, remote_blockchain, fetcher }
+	}
+
+	/// Call the runtime API `method` with an extrinsic and its length at `at`, or at the best
+	/// block.
+	fn call_with_extrinsic<R: Decode + Send + 'static>(
+		&self,
+		method: &'static str,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<R> {
+		let encoded_len = encoded_xt.len() as u32;
+		let uxt = match <Block as BlockT>::Extrinsic::decode(&mut &*encoded_xt) {
+			Ok(uxt) => uxt,
+			Err(e) => return Box::new(jsonrpc_core::futures::future::err(RpcError {
+				code: ErrorCode::ServerError(DECODE_ERROR),
+				message: "Unable to decode the extrinsic.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})),
+		};
+		let call_data = (uxt, encoded_len).encode();
+
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let fetcher = self.fetcher.clone();
+		let result = future_header(&*self.remote_blockchain, &*self.fetcher, BlockId::Hash(hash))
+			.and_then(move |header| async move {
+				let header = header.ok_or_else(|| ClientError::UnknownBlock(hash.to_string()))?;
+				fetcher.remote_call(RemoteCallRequest {
+					block: hash,
+					header,
+					method: method.into(),
+					call_data,
+					retry_count: None,
+				}).await
+			})
+			.map(move |result| {
+				let bytes = result.map_err(|e| RpcError {
+					code: ErrorCode::ServerError(RUNTIME_ERROR),
+					message: format!("Unable to call {}.", method),
+					data: Some(format!("{:?}", e).into()),
+				})?;
+				R::decode(&mut &bytes[..]).map_err(|e| RpcError {
+					code: ErrorCode::ServerError(RUNTIME_ERROR),
+					message: format!("Unable to decode the result of {}.", method),
+					data: Some(format!("{:?}", e).into()),
+				})
+			});
+		Box::new(result.boxed().compat())
+	}
+}
+
+impl<C, F> LightTransactionPaymentApi for RemoteRuntime<C, F> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+{
+	fn query_info(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<RuntimeDispatchInfo<Balance>> {
+		self.call_with_extrinsic("TransactionPaymentApi_query_info", encoded_xt, at)
+	}
+
+	fn query_fee_details(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<FeeDetails<NumberOrHex>> {
+		let details = self.call_with_extrinsic::<FeeDetails<Balance>>(
+			"TransactionPaymentApi_query_fee_details",
+			encoded_xt,
+			at,
+		);
+		// Balances are reported as `NumberOrHex`, as by the full node.
+		Box::new(details.map(|details| FeeDetails {
+			inclusion_fee: details.inclusion_fee.map(|fee| InclusionFee {
+				base_fee: fee.base_fee.into(),
+				len_fee: fee.len_fee.into(),
+				adjusted_weight_fee: fee.adjusted_weight_fee.into(),
+			}),
+			tip: Default::default(),
+		}))
+	}
+}
+
+impl<C, F> LightSponsorshipApi for RemoteRuntime<C, F> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+{
+	fn query_info(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<Hash>,
+	) -> BoxFuture<SponsoredDispatchInfo<AccountId, Balance>> {
+		self.call_with_extrinsic("SponsorshipApi_query_info", encoded_xt, at)
+	}
+}
@@ -0,0 +1,88 @@
+//! Conversions between human-readable amounts, such as `12.5 UNIT`, and raw balances, in the unit
+//! of the chain. Served by full and light nodes alike, as they need no runtime state.
+
+use std::convert::TryFrom;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use node_template_runtime::{node_primitives::balance::Unit, Balance};
+use sp_rpc::number::NumberOrHex;
+
+/// The amount could not be parsed.
+const PARSE_ERROR: i64 = 1;
+/// The balance does not fit in a `Balance`.
+const OVERFLOW: i64 = 2;
+
+/// Formats and parses balances in the unit of the chain.
+#[rpc]
+pub trait UnitsApi {
+	/// The symbol and number of decimals of the token.
+	#[rpc(name = "units_unit")]
+	fn unit(&self) -> Result<Unit>;
+
+	/// Format a raw balance in tokens, e.g. `12.5 UNIT`.
+	#[rpc(name = "units_formatBalance")]
+	fn format_balance(&self, balance: NumberOrHex) -> Result<String>;
+
+	/// Parse an amount in tokens, e.g. `12.5 UNIT`, into a raw balance.
+	#[rpc(name = "units_parseBalance")]
+	fn parse_balance(&self, amount: String) -> Result<NumberOrHex>;
+}
+
+/// Implements [`UnitsApi`] for a given unit.
+pub struct Units {
+	unit: Unit,
+}
+
+impl Units {
+	/// Format and parse balances in `unit`.
+	pub fn new(unit: Unit) -> Self {
+		Units { unit }
+	}
+}
+
+impl UnitsApi for Units {
+	fn unit(&self) -> Result<Unit> {
+		Ok(self.unit.clone())
+	}
+
+	fn format_balance(&self, balance: NumberOrHex) -> Result<String> {
+		let balance = Balance::try_from(balance).map_err(|_| RpcError {
+			code: ErrorCode::ServerError(OVERFLOW),
+			message: "Balance too large".into(),
+			data: None,
+		})?;
+		Ok(self.unit.format(balance))
+	}
+
+	fn parse_balance(&self, amount: String) -> Result<NumberOrHex> {
+		self.unit.parse(&amount).map(Into::into).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(PARSE_ERROR),
+			message: format!("Unable to parse `{}`: {}", amount, e),
+			data: None,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn units() -> Units {
+		Units::new(Unit::new(14, "UNIT"))
+	}
+
+	#[test]
+	fn formats_and_parses_balances() {
+		let balance = NumberOrHex::from(1_250_000_000_000_000 as Balance);
+		assert_eq!(units().format_balance(balance).unwrap(), "12.5 UNIT");
+		assert_eq!(units().parse_balance("12.5 UNIT".into()).unwrap(), balance);
+	}
+
+	#[test]
+	fn rejects_invalid_amounts() {
+		assert!(units().parse_balance("12.5 DOT".into()).is_err());
+		assert!(units().parse_balance("1000".into()).is_err());
+		let too_large = NumberOrHex::Hex(sp_core::U256::from(Balance::max_value()) + 1);
+		assert!(units().format_balance(too_large).is_err());
+	}
+}
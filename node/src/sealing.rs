@@ -0,0 +1,218 @@
+//! Manual, instant and interval sealing for development chains.
+//!
+//! With `--dev --sealing <mode>` the node authors blocks with `sc-consensus-manual-seal` instead of
+//! Aura and finalizes them without GRANDPA, so tests do not have to wait for slots:
+//!
+//! - `manual`: blocks are only authored through the `engine_createBlock` RPC and finalized through
+//!   `engine_finalizeBlock`;
+//! - `instant`: a block is authored and finalized as soon as a transaction enters the pool;
+//! - `interval=<ms>`: a block, possibly empty, is authored and finalized every `<ms>` milliseconds.
+//!
+//! The runtime still expects Aura, so every block carries an Aura pre-digest for the slot of its
+//! timestamp, and timestamps advance by at least one slot per block however fast blocks are made,
+//! also across restarts of a node with a persistent database.
+
+use std::{
+	str::FromStr,
+	sync::atomic::{AtomicU64, Ordering},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use codec::{Decode, Encode};
+use futures::{Stream, StreamExt};
+use node_template_runtime::{opaque::Block, Hash};
+use sc_client_api::{Backend, StorageProvider};
+use sc_consensus_manual_seal::{
+	consensus::ConsensusDataProvider, EngineCommand, Error as SealError,
+};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockImportParams;
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_inherents::{InherentData, InherentIdentifier, ProvideInherentData};
+use sp_runtime::{
+	generic::{BlockId, DigestItem},
+	traits::Block as BlockT,
+	DigestFor,
+};
+use sp_timestamp::{InherentError, TimestampInherentData, INHERENT_IDENTIFIER};
+
+/// How blocks are authored on a development chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Only on `engine_createBlock`.
+	Manual,
+	/// As soon as a transaction enters the pool.
+	Instant,
+	/// Every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"manual" => Ok(Sealing::Manual),
+			"instant" => Ok(Sealing::Instant),
+			_ => s.strip_prefix("interval=")
+				.and_then(|ms| ms.parse().ok())
+				.filter(|ms| *ms > 0)
+				.map(Sealing::Interval)
+				.ok_or_else(|| format!(
+					"Invalid sealing `{}`, expected `manual`, `instant` or `interval=<ms>`", s,
+				)),
+		}
+	}
+}
+
+/// The command that seals and finalizes a new block on top of the best one.
+fn seal_block(create_empty: bool) -> EngineCommand<Hash> {
+	EngineCommand::SealNewBlock { create_empty, finalize: true, parent_hash: None, sender: None }
+}
+
+/// Blocks sealed every `interval`.
+pub fn interval_commands(interval: Duration) -> impl Stream<Item = EngineCommand<Hash>> {
+	futures::stream::unfold((), move |()| async move {
+		futures_timer::Delay::new(interval).await;
+		Some((seal_block(true), ()))
+	})
+}
+
+/// Blocks sealed whenever a transaction is imported into the pool.
+pub fn instant_commands<S: Stream + Send>(
+	imports: S,
+) -> impl Stream<Item = EngineCommand<Hash>> {
+	imports.map(|_| seal_block(false))
+}
+
+/// Timestamp inherent that advances by at least one slot per block.
+///
+/// Uses the wall clock unless that would put two blocks in the same slot, in which case it steps
+/// one slot past the previous block.
+pub struct SealTimestamp {
+	slot_duration: u64,
+	last: AtomicU64,
+}
+
+impl SealTimestamp {
+	/// A provider for a chain with `slot_duration` milliseconds per slot, whose best block has
+	/// timestamp `last`.
+	pub fn new(slot_duration: u64, last: u64) -> Self {
+		Self { slot_duration, last: AtomicU64::new(last) }
+	}
+}
+
+/// `Timestamp::now` of the best block of `client`, 0 at genesis.
+pub fn best_timestamp<C, B>(client: &C) -> Result<u64, String> where
+	C: HeaderBackend<Block> + StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let at = BlockId::Hash(client.info().best_hash);
+	let key = StorageKey([twox_128(b"Timestamp"), twox_128(b"Now")].concat());
+	match client.storage(&at, &key).map_err(|e| e.to_string())? {
+		Some(data) => u64::decode(&mut &data.0[..])
+			.map_err(|e| format!("Unable to decode the timestamp of the best block: {}", e)),
+		None => Ok(0),
+	}
+}
+
+impl ProvideInherentData for SealTimestamp {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map_err(|_| "Current time is before unix epoch")?
+			.as_millis() as u64;
+		let slot_duration = self.slot_duration;
+		let previous = self.last.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+			Some(now.max(last + slot_duration))
+		}).expect("the closure always returns `Some`; qed");
+		inherent_data.put_data(INHERENT_IDENTIFIER, &now.max(previous + slot_duration))
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	}
+}
+
+/// Adds the Aura pre-digest the runtime expects, for the slot of the block's timestamp.
+pub struct AuraDigestProvider<Transaction> {
+	slot_duration: u64,
+	_marker: std::marker::PhantomData<fn() -> Transaction>,
+}
+
+impl<Transaction> AuraDigestProvider<Transaction> {
+	/// A provider for a chain with `slot_duration` milliseconds per slot.
+	pub fn new(slot_duration: u64) -> Self {
+		Self { slot_duration, _marker: Default::default() }
+	}
+}
+
+impl<Transaction> ConsensusDataProvider<Block> for AuraDigestProvider<Transaction> {
+	type Transaction = Transaction;
+
+	fn create_digest(
+		&self,
+		_parent: &<Block as BlockT>::Header,
+		inherents: &InherentData,
+	) -> Result<DigestFor<Block>, SealError> {
+		let timestamp = inherents.timestamp_inherent_data()
+			.map_err(|e| SealError::StringError(format!("{:?}", e)))?;
+		let slot = timestamp / self.slot_duration;
+		Ok(DigestFor::<Block> {
+			logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+		})
+	}
+
+	fn append_block_import(
+		&self,
+		_parent: &<Block as BlockT>::Header,
+		_params: &mut BlockImportParams<Block, Self::Transaction>,
+		_inherents: &InherentData,
+	) -> Result<(), SealError> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_sealing_modes() {
+		assert_eq!("manual".parse(), Ok(Sealing::Manual));
+		assert_eq!("instant".parse(), Ok(Sealing::Instant));
+		assert_eq!("interval=500".parse(), Ok(Sealing::Interval(500)));
+		assert!("interval=0".parse::<Sealing>().is_err());
+		assert!("interval".parse::<Sealing>().is_err());
+		assert!("aura".parse::<Sealing>().is_err());
+	}
+
+	#[test]
+	fn timestamps_advance_by_at_least_a_slot() {
+		let provider = SealTimestamp::new(3_000, 0);
+		let timestamp = || {
+			let mut data = InherentData::new();
+			provider.provide_inherent_data(&mut data).unwrap();
+			data.timestamp_inherent_data().unwrap()
+		};
+		let first = timestamp();
+		let second = timestamp();
+		let third = timestamp();
+		assert!(second >= first + 3_000);
+		assert!(third >= second + 3_000);
+	}
+
+	#[test]
+	fn timestamps_follow_the_best_block() {
+		// A best block in the future, e.g. after sealing faster than the slot duration.
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+		let best = now + 60_000;
+		let provider = SealTimestamp::new(3_000, best);
+		let mut data = InherentData::new();
+		provider.provide_inherent_data(&mut data).unwrap();
+		assert_eq!(data.timestamp_inherent_data().unwrap(), best + 3_000);
+	}
+}
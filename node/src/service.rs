@@ -10,7 +10,11 @@ use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::{AuthorityPair as AuraPair};
 use sc_finality_grandpa::SharedVoterState;
-use sc_keystore::LocalKeystore;
+use remote_keystore::{Credentials, RemoteKeystore};
+use futures::StreamExt;
+use sp_transaction_pool::TransactionPool;
+use crate::sealing::{self, Sealing};
+use crate::chain_spec::BackoffParams;
 
 // Our native executor instance.
 native_executor_instance!(
@@ -38,10 +42,6 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
 	)
 >, ServiceError> {
-	if config.keystore_remote.is_some() {
-		return Err(ServiceError::Other(
-			format!("Remote Keystores are not supported.")))
-	}
 	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
 
 	let (client, backend, keystore_container, task_manager) =
@@ -84,15 +84,51 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	})
 }
 
-fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
-	// FIXME: here would the concrete keystore be built,
-	//        must return a concrete type (NOT `LocalKeystore`) that
-	//        implements `CryptoStore` and `SyncCryptoStore`
-	Err("Remote Keystore not supported.")
+/// A keystore whose keys are held by the signer at `url`, see the `remote-keystore` crate.
+fn remote_keystore(
+	url: &String,
+	credentials: Option<&Credentials>,
+) -> Result<Arc<RemoteKeystore>, String> {
+	let credentials = credentials
+		.ok_or_else(|| "`--keystore-uri` requires `--keystore-token-file`".to_string())?;
+	RemoteKeystore::connect(url, credentials)
+		.map(Arc::new)
+		.map_err(|e| e.to_string())
+}
+
+/// Refuse `--keystore-uri` on nodes that never use the remote keystore, rather than ignoring it.
+fn ensure_no_remote_keystore(config: &Configuration, node: &str) -> Result<(), ServiceError> {
+	match &config.keystore_remote {
+		Some(url) => Err(ServiceError::Other(format!(
+			"{} nodes do not support the remote keystore of `--keystore-uri {}`", node, url,
+		))),
+		None => Ok(()),
+	}
+}
+
+/// Options of a full node that are not part of its `Configuration`.
+#[derive(Debug, Clone, Default)]
+pub struct FullOptions {
+	/// Follow finality as a GRANDPA observer instead of running the voter. Validators always run
+	/// the voter.
+	pub grandpa_observer: bool,
+	/// Authoring backoff, resolved from the chain spec and the command line.
+	pub backoff: Option<BackoffParams>,
+	/// Credentials for the remote signer of `--keystore-uri`, if given.
+	pub keystore_credentials: Option<Credentials>,
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	options: FullOptions,
+) -> Result<TaskManager, ServiceError> {
+	let FullOptions { grandpa_observer, backoff, keystore_credentials } = options;
+	if grandpa_observer && config.role.is_authority() {
+		return Err(ServiceError::Other(
+			"Validators must run the GRANDPA voter, not the observer".into()))
+	}
+
 	let sc_service::PartialComponents {
 		client, backend, mut task_manager, import_queue, mut keystore_container, select_chain, transaction_pool,
 		inherent_data_providers,
@@ -100,7 +136,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	} = new_partial(&config)?;
 
 	if let Some(url) = &config.keystore_remote {
-		match remote_keystore(url) {
+		match remote_keystore(url, keystore_credentials.as_ref()) {
 			Ok(k) => keystore_container.set_remote_keystore(k),
 			Err(e) => {
 				return Err(ServiceError::Other(
@@ -128,9 +164,13 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		);
 	}
 
+	let extensions = crate::chain_spec::Extensions::try_get(&*config.chain_spec)
+		.cloned()
+		.unwrap_or_default();
+
 	let role = config.role.clone();
-	let force_authoring = config.force_authoring;
-	let backoff_authoring_blocks: Option<()> = None;
+	let force_authoring = config.force_authoring || extensions.aura.force_authoring;
+	let backoff_authoring_blocks = backoff.as_ref().map(crate::backoff::strategy);
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
@@ -138,12 +178,15 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let unit = crate::chain_spec::token_unit_of(&*config.chain_spec);
 
 		Box::new(move |deny_unsafe, _| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				command_sink: None,
+				unit: unit.clone(),
 			};
 
 			crate::rpc::create_full(deps)
@@ -167,6 +210,27 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		},
 	)?;
 
+	if let Some(registry) = prometheus_registry.as_ref() {
+		task_manager.spawn_handle().spawn(
+			"listen-metrics",
+			crate::metrics::run(client.clone(), transaction_pool.clone(), registry)?,
+		);
+	}
+
+	if let (true, Some(backoff), Some(registry)) =
+		(role.is_authority(), backoff, prometheus_registry.as_ref())
+	{
+		task_manager.spawn_handle().spawn(
+			"authoring-backoff-metrics",
+			crate::backoff::monitor(
+				client.clone(),
+				backoff,
+				sc_consensus_aura::slot_duration(&*client)?.get(),
+				registry,
+			)?,
+		);
+	}
+
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -206,28 +270,33 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	};
 
 	let grandpa_config = sc_finality_grandpa::Config {
-		// FIXME #1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(333),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(extensions.grandpa.gossip_duration_ms),
+		justification_period: extensions.grandpa.justification_period,
 		name: Some(name),
-		observer_enabled: false,
+		observer_enabled: grandpa_observer,
 		keystore,
 		is_authority: role.is_network_authority(),
 	};
 
-	if enable_grandpa {
+	if enable_grandpa && grandpa_observer {
+		// the observer only follows the commit messages of the voters, and
+		// needs neither a keystore nor the shared voter state.
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"grandpa-observer",
+			sc_finality_grandpa::run_grandpa_observer(grandpa_config, grandpa_link, network)?
+		);
+	} else if enable_grandpa {
 		// start the full GRANDPA voter
-		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
-		// this point the full voter should provide better guarantees of block
-		// and vote data availability than the observer. The observer has not
-		// been tested extensively yet and having most nodes in a network run it
-		// could lead to finality stalls.
+		// NOTE: non-authorities can run the GRANDPA observer protocol instead
+		// with `--grandpa-observer`, but the full voter provides better
+		// guarantees of block and vote data availability. Having most nodes in
+		// a network run the observer could lead to finality stalls.
 		let grandpa_config = sc_finality_grandpa::GrandpaParams {
 			config: grandpa_config,
 			link: grandpa_link,
 			network,
 			telemetry_on_connect: telemetry_connection_notifier.map(|x| x.on_connect_stream()),
-			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
+			voting_rule: crate::voting_rules::build(&extensions.grandpa.voting_rules),
 			prometheus_registry,
 			shared_voter_state: SharedVoterState::empty(),
 		};
@@ -244,8 +313,138 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	Ok(task_manager)
 }
 
+/// Builds a new service for a full client of a development chain that seals blocks according to
+/// `sealing` instead of running Aura and GRANDPA.
+pub fn new_dev(config: Configuration, sealing: Sealing) -> Result<TaskManager, ServiceError> {
+	ensure_no_remote_keystore(&config, "Sealing")?;
+	let (client, backend, keystore_container, mut task_manager) =
+		sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
+	let client = Arc::new(client);
+
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+		config.transaction_pool.clone(),
+		config.role.is_authority().into(),
+		config.prometheus_registry(),
+		task_manager.spawn_handle(),
+		client.clone(),
+	);
+
+	let import_queue = sc_consensus_manual_seal::import_queue(
+		Box::new(client.clone()),
+		&task_manager.spawn_handle(),
+		config.prometheus_registry(),
+	);
+
+	let (network, network_status_sinks, system_rpc_tx, network_starter) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			on_demand: None,
+			block_announce_validator_builder: None,
+		})?;
+
+	if config.offchain_worker.enabled {
+		sc_service::build_offchain_workers(
+			&config, backend.clone(), task_manager.spawn_handle(), client.clone(), network.clone(),
+		);
+	}
+
+	let prometheus_registry = config.prometheus_registry().cloned();
+	let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let pool = transaction_pool.clone();
+		let unit = crate::chain_spec::token_unit_of(&*config.chain_spec);
+
+		Box::new(move |deny_unsafe, _| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: Some(command_sink.clone()),
+				unit: unit.clone(),
+			};
+
+			crate::rpc::create_full(deps)
+		})
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		network,
+		client: client.clone(),
+		keystore: keystore_container.sync_keystore(),
+		task_manager: &mut task_manager,
+		transaction_pool: transaction_pool.clone(),
+		rpc_extensions_builder,
+		on_demand: None,
+		remote_blockchain: None,
+		backend,
+		network_status_sinks,
+		system_rpc_tx,
+		config,
+	})?;
+
+	let proposer = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+	);
+
+	let slot_duration = sc_consensus_aura::slot_duration(&*client)?.get();
+	let inherent_data_providers = InherentDataProviders::new();
+	inherent_data_providers
+		.register_provider(sealing::SealTimestamp::new(
+			slot_duration,
+			sealing::best_timestamp(&*client)?,
+		))
+		.map_err(|e| ServiceError::Other(format!("{:?}", e)))?;
+
+	// Blocks requested over RPC are sealed in every mode.
+	let commands_stream = match sealing {
+		Sealing::Manual => rpc_commands.boxed(),
+		Sealing::Instant => futures::stream::select(
+			rpc_commands,
+			sealing::instant_commands(transaction_pool.import_notification_stream()),
+		).boxed(),
+		Sealing::Interval(millis) => futures::stream::select(
+			rpc_commands,
+			sealing::interval_commands(Duration::from_millis(millis)),
+		).boxed(),
+	};
+
+	let authorship = sc_consensus_manual_seal::run_manual_seal(
+		sc_consensus_manual_seal::ManualSealParams {
+			block_import: client.clone(),
+			env: proposer,
+			client: client.clone(),
+			pool: transaction_pool.pool().clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(
+				sealing::AuraDigestProvider::new(slot_duration),
+			)),
+			inherent_data_providers,
+		},
+	);
+
+	// the authoring task is considered essential, i.e. if it
+	// fails we take down the service with it.
+	task_manager.spawn_essential_handle().spawn_blocking("manual-seal", authorship);
+
+	network_starter.start_network();
+	Ok(task_manager)
+}
+
 /// Builds a new service for a light client.
 pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+	ensure_no_remote_keystore(&config, "Light")?;
 	let (client, backend, keystore_container, mut task_manager, on_demand) =
 		sc_service::new_light_parts::<Block, RuntimeApi, Executor>(&config)?;
 
@@ -300,12 +499,22 @@ pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		);
 	}
 
+	let light_deps = crate::rpc::LightDeps {
+		remote_blockchain: backend.remote_blockchain(),
+		fetcher: on_demand.clone(),
+		client: client.clone(),
+		pool: transaction_pool.clone(),
+		unit: crate::chain_spec::token_unit_of(&*config.chain_spec),
+	};
+
+	let rpc_extensions = crate::rpc::create_light(light_deps);
+
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		remote_blockchain: Some(backend.remote_blockchain()),
 		transaction_pool,
 		task_manager: &mut task_manager,
 		on_demand: Some(on_demand),
-		rpc_extensions_builder: Box::new(|_, _| ()),
+		rpc_extensions_builder: Box::new(sc_service::NoopRpcExtensionBuilder(rpc_extensions)),
 		config,
 		client,
 		keystore: keystore_container.sync_keystore(),
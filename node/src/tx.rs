@@ -0,0 +1,342 @@
+//! Building, signing and submitting extrinsics to a running node over its HTTP RPC.
+//!
+//! The signed extensions are those of `node_template_runtime::SignedExtra`; their implicit data
+//! (runtime version, genesis hash, era checkpoint and nonce) is read from the node.
+
+use std::{fs, path::Path, sync::Arc};
+use codec::{Decode, Encode};
+use node_template_runtime::{
+	AccountId, Balance, BalancesCall, Call, ContentAnchorCall, ContentHash, Hash, Index,
+	RoomsCall, SignedExtra, SponsorshipCall, SudoCall, SystemCall, UncheckedExtrinsic,
+	node_primitives::balance::Unit,
+};
+use sc_keystore::LocalKeystore;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{key_types, CryptoTypePublicPair, Pair as _, Public as _, SecretString, Ss58Codec},
+	hexdisplay::HexDisplay, sr25519, Bytes,
+};
+use sp_keystore::SyncCryptoStore;
+use sp_runtime::{generic::{Era, SignedPayload}, MultiSignature};
+use crate::{chain_spec, cli::{TxCall, TxCmd}};
+
+/// Signs extrinsics for one account.
+pub enum Signer {
+	/// With a key pair derived from a secret URI.
+	Pair(sr25519::Pair),
+	/// With a key of a local keystore, under the `acco` key type.
+	Keystore(Arc<LocalKeystore>, sr25519::Public),
+}
+
+impl Signer {
+	/// The signing account.
+	pub fn account(&self) -> AccountId {
+		match self {
+			Signer::Pair(pair) => pair.public().into(),
+			Signer::Keystore(_, public) => public.clone().into(),
+		}
+	}
+
+	/// Sign `message`.
+	pub fn sign(&self, message: &[u8]) -> Result<MultiSignature, String> {
+		match self {
+			Signer::Pair(pair) => Ok(pair.sign(message).into()),
+			Signer::Keystore(keystore, public) => {
+				let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, public.to_raw_vec());
+				let signature = SyncCryptoStore::sign_with(
+					&**keystore, key_types::ACCOUNT, &key, message,
+				).map_err(|e| format!("Unable to sign with the keystore: {}", e))?;
+				sr25519::Signature::decode(&mut &signature[..])
+					.map(Into::into)
+					.map_err(|e| format!("Invalid signature from the keystore: {}", e))
+			},
+		}
+	}
+}
+
+/// What the signed extensions check against the chain.
+#[derive(Debug, Clone)]
+pub struct ChainState {
+	/// `spec_version` of the runtime.
+	pub spec_version: u32,
+	/// `transaction_version` of the runtime.
+	pub transaction_version: u32,
+	/// Hash of the genesis block.
+	pub genesis_hash: Hash,
+	/// Mortality of the extrinsic.
+	pub era: Era,
+	/// Hash of the block the era starts at, the genesis block for immortal extrinsics.
+	pub era_hash: Hash,
+	/// Nonce of the signer.
+	pub nonce: Index,
+}
+
+/// Sign `call` with `signer` for a chain in `state`, with `tip`.
+pub fn build(
+	call: Call,
+	signer: &Signer,
+	state: &ChainState,
+	tip: Balance,
+) -> Result<UncheckedExtrinsic, String> {
+	let extra: SignedExtra = (
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(state.era),
+		frame_system::CheckNonce::from(state.nonce),
+		frame_system::CheckWeight::new(),
+		pallet_rate_limit::CheckRateLimit::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::from(tip).into(),
+	);
+	let additional_signed = (
+		state.spec_version,
+		state.transaction_version,
+		state.genesis_hash,
+		state.era_hash,
+		(),
+		(),
+		(),
+		(),
+	);
+	let payload = SignedPayload::from_raw(call, extra, additional_signed);
+	let signature = payload.using_encoded(|payload| signer.sign(payload))?;
+	let (call, extra, _) = payload.deconstruct();
+	Ok(UncheckedExtrinsic::new_signed(call, signer.account().into(), signature, extra))
+}
+
+/// A minimal JSON-RPC client over HTTP.
+pub struct RpcClient {
+	url: String,
+	agent: ureq::Agent,
+}
+
+#[derive(serde::Deserialize)]
+struct Response<T> {
+	result: Option<T>,
+	error: Option<Value>,
+}
+
+impl RpcClient {
+	/// A client of the node serving HTTP RPC at `url`.
+	pub fn new(url: &str) -> Self {
+		RpcClient { url: url.into(), agent: ureq::Agent::new() }
+	}
+
+	/// Call `method` with `params`.
+	pub fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, String> {
+		let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+		let response: Response<T> = self.agent.post(&self.url)
+			.send_json(request)
+			.map_err(|e| format!("Calling {} on {} failed: {}", method, self.url, e))?
+			.into_json()
+			.map_err(|e| format!("Invalid response to {}: {}", method, e))?;
+		match response {
+			Response { result: Some(result), error: None } => Ok(result),
+			Response { error: Some(error), .. } => Err(format!("{} failed: {}", method, error)),
+			Response { result: None, error: None } => Err(format!("{} returned nothing", method)),
+		}
+	}
+
+	/// The token of the chain, from its properties, or that of the development chains if they
+	/// do not name one.
+	pub fn unit(&self) -> Result<Unit, String> {
+		let properties: Value = self.call("system_properties", json!([]))?;
+		Ok(serde_json::from_value(properties).unwrap_or_else(|_| chain_spec::token_unit()))
+	}
+
+	/// Hash of the best chain's block `number`.
+	pub fn block_hash(&self, number: u64) -> Result<Hash, String> {
+		self.call::<Option<Hash>>("chain_getBlockHash", json!([number]))?
+			.ok_or_else(|| format!("Block #{} is unknown to the node", number))
+	}
+
+	/// The state a new extrinsic of `account` is checked against, valid for `mortality` blocks
+	/// from the last finalized block.
+	pub fn chain_state(&self, account: &AccountId, mortality: u64) -> Result<ChainState, String> {
+		let version: Value = self.call("state_getRuntimeVersion", json!([]))?;
+		let version_field = |name: &str| version[name].as_u64()
+			.map(|v| v as u32)
+			.ok_or_else(|| format!("The runtime version has no {}", name));
+		let genesis_hash = self.block_hash(0)?;
+		let nonce = self.call("system_accountNextIndex", json!([account.to_ss58check()]))?;
+
+		let (era, era_hash) = if mortality == 0 {
+			(Era::Immortal, genesis_hash)
+		} else {
+			let finalized: Hash = self.call("chain_getFinalizedHead", json!([]))?;
+			let header: Value = self.call("chain_getHeader", json!([finalized]))?;
+			let number = header["number"].as_str()
+				.and_then(|n| u64::from_str_radix(n.trim_start_matches("0x"), 16).ok())
+				.ok_or("Invalid header number")?;
+			let era = Era::mortal(mortality, number);
+			(era, self.block_hash(era.birth(number))?)
+		};
+
+		Ok(ChainState {
+			spec_version: version_field("specVersion")?,
+			transaction_version: version_field("transactionVersion")?,
+			genesis_hash,
+			era,
+			era_hash,
+			nonce,
+		})
+	}
+}
+
+impl TxCmd {
+	/// Build the extrinsic, then submit it or print its fee.
+	pub fn run(&self) -> Result<(), String> {
+		let signer = self.signer()?;
+		let rpc = RpcClient::new(&self.url);
+		let unit = rpc.unit()?;
+		let state = rpc.chain_state(&signer.account(), self.mortality)?;
+		let tip = parse_amount(&unit, &self.tip)?;
+		let xt = build(self.call(&unit)?, &signer, &state, tip)?;
+		let encoded = Bytes(xt.encode());
+
+		if self.dry_run {
+			let info: Value = rpc.call("payment_queryInfo", json!([encoded]))?;
+			println!("extrinsic: 0x{}", HexDisplay::from(&encoded.0));
+			println!("weight:    {}", info["weight"]);
+			println!("class:     {}", info["class"]);
+			let fee = info["partialFee"].as_str()
+				.and_then(|fee| fee.parse().ok())
+				.ok_or("Invalid fee")?;
+			println!("fee:       {}", unit.format(fee));
+			return Ok(())
+		}
+
+		let hash: Hash = rpc.call("author_submitExtrinsic", json!([encoded]))?;
+		println!("{:?}", hash);
+		Ok(())
+	}
+
+	fn signer(&self) -> Result<Signer, String> {
+		match (&self.suri, &self.keystore_path) {
+			(Some(suri), _) => sr25519::Pair::from_string(suri, None)
+				.map(Signer::Pair)
+				.map_err(|e| format!("Invalid secret URI: {:?}", e)),
+			(None, Some(path)) => {
+				let password = self.password.clone().map(SecretString::new);
+				let keystore = LocalKeystore::open(path, password)
+					.map_err(|e| format!("Unable to open the keystore: {}", e))?;
+				let signer = self.signer.as_ref().ok_or("`--signer` is required with a keystore")?;
+				let public = sr25519::Public::from_ss58check(signer)
+					.map_err(|e| format!("Invalid signer `{}`: {:?}", signer, e))?;
+				Ok(Signer::Keystore(Arc::new(keystore), public))
+			},
+			(None, None) => Err("Either `--suri` or `--keystore-path` is required".into()),
+		}
+	}
+
+	fn call(&self, unit: &Unit) -> Result<Call, String> {
+		let call = match &self.call {
+			TxCall::Transfer { dest, amount } => Call::Balances(BalancesCall::transfer(
+				parse_account(dest)?.into(),
+				parse_amount(unit, amount)?,
+			)),
+			TxCall::OpenRoom => Call::Rooms(RoomsCall::open()),
+			TxCall::JoinRoom { room } => Call::Rooms(RoomsCall::join(*room)),
+			TxCall::LeaveRoom { room } => Call::Rooms(RoomsCall::leave(*room)),
+			TxCall::FundRoom { room, amount } => {
+				Call::Sponsorship(SponsorshipCall::fund(*room, parse_amount(unit, amount)?))
+			},
+			TxCall::WithdrawRoom { room, amount } => {
+				Call::Sponsorship(SponsorshipCall::withdraw(*room, parse_amount(unit, amount)?))
+			},
+			TxCall::StopSponsoring { room } => Call::Sponsorship(SponsorshipCall::stop(*room)),
+			TxCall::RemoveAnchor { hash } => {
+				Call::ContentAnchor(ContentAnchorCall::remove(parse_content_hash(hash)?))
+			},
+			TxCall::SetCode { wasm } => {
+				// Runtime upgrades take a whole block, more than `sudo` accounts for.
+				let code = read_file(wasm)?;
+				let set_code = Call::System(SystemCall::set_code(code));
+				return Ok(Call::Sudo(SudoCall::sudo_unchecked_weight(Box::new(set_code), 0)))
+			},
+			TxCall::Raw { call } => {
+				let bytes = sp_core::bytes::from_hex(call)
+					.map_err(|e| format!("Invalid call hex: {}", e))?;
+				Call::decode(&mut &bytes[..]).map_err(|e| format!("Invalid call: {}", e))?
+			},
+		};
+		Ok(if self.sudo { Call::Sudo(SudoCall::sudo(Box::new(call))) } else { call })
+	}
+}
+
+fn parse_account(address: &str) -> Result<AccountId, String> {
+	AccountId::from_ss58check(address).map_err(|e| format!("Invalid address `{}`: {:?}", address, e))
+}
+
+fn parse_amount(unit: &Unit, amount: &str) -> Result<Balance, String> {
+	unit.parse(amount).map_err(|e| format!("Invalid amount `{}`: {}", amount, e))
+}
+
+/// A blake2-256 digest if 32 bytes long, a binary CID otherwise.
+fn parse_content_hash(hash: &str) -> Result<ContentHash, String> {
+	let bytes = sp_core::bytes::from_hex(hash)
+		.map_err(|e| format!("Invalid content hash `{}`: {}", hash, e))?;
+	let mut digest = [0u8; 32];
+	Ok(if bytes.len() == digest.len() {
+		digest.copy_from_slice(&bytes);
+		ContentHash::Blake2_256(digest)
+	} else {
+		ContentHash::Cid(bytes)
+	})
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+	fs::read(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::traits::Verify;
+
+	fn state() -> ChainState {
+		ChainState {
+			spec_version: 1,
+			transaction_version: 1,
+			genesis_hash: Hash::repeat_byte(1),
+			era: Era::mortal(64, 100),
+			era_hash: Hash::repeat_byte(2),
+			nonce: 5,
+		}
+	}
+
+	#[test]
+	fn signs_what_the_runtime_checks() {
+		let signer = Signer::Pair(AccountKeyring::Alice.pair());
+		let call = Call::Balances(
+			BalancesCall::transfer(AccountKeyring::Bob.to_account_id().into(), 7)
+		);
+		let xt = build(call.clone(), &signer, &state(), 3).unwrap();
+
+		let (address, signature, extra) = xt.signature.clone().unwrap();
+		assert_eq!(address, AccountKeyring::Alice.to_account_id().into());
+		let state = state();
+		let payload = SignedPayload::from_raw(call, extra, (
+			state.spec_version, state.transaction_version, state.genesis_hash, state.era_hash,
+			(), (), (), (),
+		));
+		assert!(payload.using_encoded(|payload| {
+			signature.verify(payload, &AccountKeyring::Alice.to_account_id())
+		}));
+
+		let inspected = crate::inspect::Inspected::new(None, xt, Vec::new());
+		assert_eq!(inspected.nonce(), Some(5));
+		assert_eq!(inspected.tip(), Some(3));
+		assert_eq!(inspected.era(), Some(Era::mortal(64, 100)));
+	}
+
+	#[test]
+	fn parses_content_hashes() {
+		let digest = format!("0x{}", "11".repeat(32));
+		assert_eq!(parse_content_hash(&digest), Ok(ContentHash::Blake2_256([0x11; 32])));
+		assert_eq!(parse_content_hash("0x0155"), Ok(ContentHash::Cid(vec![1, 0x55])));
+		assert!(parse_content_hash("cid").is_err());
+	}
+}
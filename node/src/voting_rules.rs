@@ -0,0 +1,65 @@
+//! GRANDPA voting rules configured through the chain spec, see `chain_spec::VotingRules`.
+
+use std::sync::Arc;
+use node_template_runtime::{opaque::Block, BlockNumber};
+use sc_finality_grandpa::{
+	BeforeBestBlockBy, ThreeQuartersOfTheUnfinalizedChain, VotingRule, VotingRuleResult,
+	VotingRulesBuilder,
+};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use crate::chain_spec::VotingRules;
+
+/// The voting rule made of all rules enabled in `rules`.
+pub fn build<B>(rules: &VotingRules) -> impl VotingRule<Block, B> + Clone where
+	B: HeaderBackend<Block> + 'static,
+{
+	let mut builder = VotingRulesBuilder::new();
+	if let Some(blocks) = rules.before_best_block_by {
+		builder = builder.add(BeforeBestBlockBy(blocks));
+	}
+	if rules.three_quarters_of_unfinalized_chain {
+		builder = builder.add(ThreeQuartersOfTheUnfinalizedChain);
+	}
+	if let Some(blocks) = rules.max_votes_ahead {
+		builder = builder.add(MaxVotesAhead(blocks));
+	}
+	builder.build()
+}
+
+/// Never vote more than the given number of blocks past the last finalized block, so that a
+/// stalled network catches up in bounded steps.
+#[derive(Clone)]
+pub struct MaxVotesAhead(pub BlockNumber);
+
+impl<B: HeaderBackend<Block>> VotingRule<Block, B> for MaxVotesAhead {
+	fn restrict_vote(
+		&self,
+		backend: Arc<B>,
+		base: &<Block as BlockT>::Header,
+		_best_target: &<Block as BlockT>::Header,
+		current_target: &<Block as BlockT>::Header,
+	) -> VotingRuleResult<Block> {
+		let target_number = base.number().saturating_add(self.0);
+		restrict_to(backend, target_number, current_target.clone())
+	}
+}
+
+/// Walk back from `current_target` to its ancestor at `target_number`, unless it is already at
+/// or below it.
+fn restrict_to<B: HeaderBackend<Block>>(
+	backend: Arc<B>,
+	target_number: BlockNumber,
+	current_target: <Block as BlockT>::Header,
+) -> VotingRuleResult<Block> {
+	Box::pin(async move {
+		if *current_target.number() <= target_number {
+			return None
+		}
+		let mut target = current_target;
+		while *target.number() > target_number {
+			target = backend.header(BlockId::Hash(*target.parent_hash())).ok()??;
+		}
+		Some((target.hash(), *target.number()))
+	})
+}
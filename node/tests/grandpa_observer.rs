@@ -0,0 +1,101 @@
+//! A node started with `--grandpa-observer` follows the finality of the voters.
+
+use std::{
+	net::TcpListener,
+	process::{Child, Command, Stdio},
+	thread,
+	time::{Duration, Instant},
+};
+use serde_json::{json, Value};
+
+/// Node key of the validator, and the peer id it results in.
+const VALIDATOR_NODE_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+const VALIDATOR_PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+/// Kills the node when dropped, also when the test fails.
+struct Node(Child);
+
+impl Drop for Node {
+	fn drop(&mut self) {
+		let _ = self.0.kill();
+		let _ = self.0.wait();
+	}
+}
+
+/// A port nothing listens on, for a node to bind to.
+fn free_port() -> u16 {
+	TcpListener::bind("127.0.0.1:0")
+		.and_then(|listener| listener.local_addr())
+		.map(|addr| addr.port())
+		.expect("No free port")
+}
+
+fn start_node(args: &[&str]) -> Node {
+	Node(Command::new(env!("CARGO_BIN_EXE_node-template"))
+		.args(&["--tmp", "--no-mdns", "--no-prometheus", "--no-telemetry"])
+		.args(args)
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.unwrap())
+}
+
+fn rpc(port: u16, method: &str, params: Value) -> Option<Value> {
+	let response: Value = ureq::post(&format!("http://127.0.0.1:{}", port))
+		.send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+		.ok()?
+		.into_json()
+		.ok()?;
+	response.get("result").cloned()
+}
+
+/// Number of the last block finalized by the node listening for RPC on `port`.
+fn finalized_number(port: u16) -> Option<u64> {
+	let hash = rpc(port, "chain_getFinalizedHead", json!([]))?;
+	let header = rpc(port, "chain_getHeader", json!([hash]))?;
+	u64::from_str_radix(header["number"].as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+#[test]
+fn observer_follows_finality() {
+	let (validator_port, validator_rpc, observer_rpc) = (free_port(), free_port(), free_port());
+	let _validator = start_node(&[
+		"--dev",
+		"--node-key", VALIDATOR_NODE_KEY,
+		"--port", &validator_port.to_string(),
+		"--rpc-port", &validator_rpc.to_string(),
+		"--ws-port", &free_port().to_string(),
+	]);
+	let _observer = start_node(&[
+		"--chain", "dev",
+		"--grandpa-observer",
+		"--bootnodes",
+		&format!("/ip4/127.0.0.1/tcp/{}/p2p/{}", validator_port, VALIDATOR_PEER_ID),
+		"--port", &free_port().to_string(),
+		"--rpc-port", &observer_rpc.to_string(),
+		"--ws-port", &free_port().to_string(),
+	]);
+
+	let deadline = Instant::now() + Duration::from_secs(120);
+	while Instant::now() < deadline {
+		if finalized_number(observer_rpc).unwrap_or(0) >= 3 {
+			return
+		}
+		thread::sleep(Duration::from_secs(1));
+	}
+	panic!(
+		"The observer did not finalize block 3 in time; the validator finalized {:?}",
+		finalized_number(validator_rpc),
+	);
+}
+
+#[test]
+fn validators_cannot_be_observers() {
+	let status = Command::new(env!("CARGO_BIN_EXE_node-template"))
+		.args(&["--dev", "--tmp", "--grandpa-observer", "--no-prometheus", "--no-telemetry"])
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.status()
+		.unwrap();
+	assert!(!status.success());
+}
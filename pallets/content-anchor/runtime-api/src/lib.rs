@@ -0,0 +1,26 @@
+//! Runtime API definition for the content anchor pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_content_anchor::{Anchor, ContentHash};
+
+sp_api::decl_runtime_apis! {
+	/// Lookup of anchored content by hash or by room.
+	pub trait ContentAnchorApi<AccountId, RoomId, Balance, BlockNumber> where
+		AccountId: Codec,
+		RoomId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The anchor registered for `hash`, if any.
+		fn anchor(hash: ContentHash) -> Option<Anchor<AccountId, RoomId, Balance, BlockNumber>>;
+
+		/// All anchors registered for `room`.
+		fn anchors_by_room(
+			room: RoomId,
+		) -> Vec<(ContentHash, Anchor<AccountId, RoomId, Balance, BlockNumber>)>;
+	}
+}
@@ -0,0 +1,56 @@
+//! Benchmarking setup for pallet-content-anchor
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as ContentAnchor;
+
+fn worst_case_anchor<T: Config>() -> (ContentHash, Vec<u8>) {
+	(
+		ContentHash::Cid(vec![1; T::MaxCidLength::get() as usize]),
+		vec![b'a'; T::MaxLicenseLength::get() as usize],
+	)
+}
+
+benchmarks! {
+	anchor {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let room: T::RoomId = Decode::decode(&mut &[0u8; 32][..]).unwrap();
+		let (hash, license) = worst_case_anchor::<T>();
+	}: _(RawOrigin::Signed(caller), room, hash.clone(), 1 << 30, license)
+	verify {
+		assert!(Anchors::<T>::contains_key(&hash));
+	}
+
+	remove {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let room: T::RoomId = Decode::decode(&mut &[0u8; 32][..]).unwrap();
+		let (hash, license) = worst_case_anchor::<T>();
+		ContentAnchor::<T>::anchor(
+			RawOrigin::Signed(caller.clone()).into(), room, hash.clone(), 1 << 30, license,
+		)?;
+	}: _(RawOrigin::Signed(caller), hash.clone())
+	verify {
+		assert!(!Anchors::<T>::contains_key(&hash));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_anchor::<Test>());
+			assert_ok!(test_benchmark_remove::<Test>());
+		});
+	}
+}
@@ -0,0 +1,233 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Content anchoring for room recordings.
+//!
+//! Hosts anchor the hash of a recording (its IPFS CID or a blake2 digest), together with its size
+//! and license tag, to prove that the content existed at a given block without storing any audio
+//! on chain. Every anchor reserves a deposit proportional to its encoded size, which is returned
+//! when the owner removes it.
+//!
+//! Anchors can be looked up by hash or by room, see `pallet-content-anchor-runtime-api`.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+	storage::IterableStorageDoubleMap,
+	traits::{Currency, Get, ReservableCurrency},
+	Parameter,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{traits::{Member, Saturating}, RuntimeDebug};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The hash identifying a piece of content.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ContentHash {
+	/// A blake2-256 digest of the content.
+	Blake2_256([u8; 32]),
+	/// An IPFS content identifier in its binary form.
+	Cid(Vec<u8>),
+}
+
+/// An anchored piece of content.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Anchor<AccountId, RoomId, Balance, BlockNumber> {
+	/// The account that anchored the content and holds the deposit.
+	pub owner: AccountId,
+	/// The room the content was recorded in.
+	pub room: RoomId,
+	/// Size of the content in bytes.
+	pub size: u64,
+	/// License tag of the content, e.g. `CC-BY-4.0`.
+	pub license: Vec<u8>,
+	/// The deposit reserved from the owner.
+	pub deposit: Balance,
+	/// The block in which the content was anchored.
+	pub anchored_at: BlockNumber,
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The currency in which anchor deposits are reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Identifier of a room.
+	type RoomId: Parameter + Member + Copy;
+	/// The base deposit reserved for every anchor.
+	type AnchorDepositBase: Get<BalanceOf<Self>>;
+	/// The additional deposit reserved per byte of the encoded hash and license tag.
+	type AnchorDepositPerByte: Get<BalanceOf<Self>>;
+	/// Maximum length of a CID in bytes.
+	type MaxCidLength: Get<u32>;
+	/// Maximum length of a license tag in bytes.
+	type MaxLicenseLength: Get<u32>;
+	/// Maximum number of anchors per room.
+	type MaxAnchorsPerRoom: Get<u32>;
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as ContentAnchor {
+		/// Anchors by content hash.
+		Anchors get(fn anchor_of):
+			map hasher(blake2_128_concat) ContentHash
+			=> Option<Anchor<T::AccountId, T::RoomId, BalanceOf<T>, T::BlockNumber>>;
+
+		/// Index of the anchored content of every room.
+		RoomAnchors:
+			double_map hasher(blake2_128_concat) T::RoomId, hasher(blake2_128_concat) ContentHash
+			=> ();
+
+		/// Number of anchors in every room.
+		RoomAnchorCount get(fn room_anchor_count): map hasher(blake2_128_concat) T::RoomId => u32;
+
+		/// Number of rooms with at least one anchor.
+		pub AnchoredRooms get(fn anchored_rooms): u32;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		RoomId = <T as Config>::RoomId,
+	{
+		/// Content was anchored. [owner, room, hash]
+		Anchored(AccountId, RoomId, ContentHash),
+		/// An anchor was removed and its deposit returned. [owner, room, hash]
+		Removed(AccountId, RoomId, ContentHash),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The content is already anchored.
+		AlreadyAnchored,
+		/// No anchor exists for the content hash.
+		NotAnchored,
+		/// Only the owner of an anchor can remove it.
+		NotOwner,
+		/// The CID is longer than `MaxCidLength`.
+		CidTooLong,
+		/// The license tag is longer than `MaxLicenseLength`.
+		LicenseTooLong,
+		/// The room already holds `MaxAnchorsPerRoom` anchors.
+		TooManyAnchors,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		const AnchorDepositBase: BalanceOf<T> = T::AnchorDepositBase::get();
+		const AnchorDepositPerByte: BalanceOf<T> = T::AnchorDepositPerByte::get();
+		const MaxCidLength: u32 = T::MaxCidLength::get();
+		const MaxLicenseLength: u32 = T::MaxLicenseLength::get();
+		const MaxAnchorsPerRoom: u32 = T::MaxAnchorsPerRoom::get();
+
+		fn deposit_event() = default;
+
+		/// Anchor the hash of a recording made in `room`.
+		///
+		/// Reserves `AnchorDepositBase` plus `AnchorDepositPerByte` for every byte of the encoded
+		/// hash and license tag.
+		#[weight = T::WeightInfo::anchor()]
+		pub fn anchor(
+			origin,
+			room: T::RoomId,
+			hash: ContentHash,
+			size: u64,
+			license: Vec<u8>,
+		) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			if let ContentHash::Cid(cid) = &hash {
+				ensure!(cid.len() as u32 <= T::MaxCidLength::get(), Error::<T>::CidTooLong);
+			}
+			ensure!(
+				license.len() as u32 <= T::MaxLicenseLength::get(),
+				Error::<T>::LicenseTooLong,
+			);
+			ensure!(!Anchors::<T>::contains_key(&hash), Error::<T>::AlreadyAnchored);
+			let count = RoomAnchorCount::<T>::get(room);
+			ensure!(count < T::MaxAnchorsPerRoom::get(), Error::<T>::TooManyAnchors);
+
+			let deposit = Self::deposit_for(&hash, &license);
+			T::Currency::reserve(&who, deposit)?;
+
+			Anchors::<T>::insert(&hash, Anchor {
+				owner: who.clone(),
+				room,
+				size,
+				license,
+				deposit,
+				anchored_at: frame_system::Module::<T>::block_number(),
+			});
+			RoomAnchors::<T>::insert(room, &hash, ());
+			RoomAnchorCount::<T>::insert(room, count + 1);
+			if count == 0 {
+				AnchoredRooms::mutate(|rooms| *rooms += 1);
+			}
+
+			Self::deposit_event(RawEvent::Anchored(who, room, hash));
+			Ok(())
+		}
+
+		/// Remove an anchor owned by the origin and return its deposit.
+		#[weight = T::WeightInfo::remove()]
+		pub fn remove(origin, hash: ContentHash) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			let anchor = Anchors::<T>::get(&hash).ok_or(Error::<T>::NotAnchored)?;
+			ensure!(anchor.owner == who, Error::<T>::NotOwner);
+
+			Anchors::<T>::remove(&hash);
+			RoomAnchors::<T>::remove(anchor.room, &hash);
+			let count = RoomAnchorCount::<T>::get(anchor.room).saturating_sub(1);
+			if count == 0 {
+				RoomAnchorCount::<T>::remove(anchor.room);
+				AnchoredRooms::mutate(|rooms| *rooms = rooms.saturating_sub(1));
+			} else {
+				RoomAnchorCount::<T>::insert(anchor.room, count);
+			}
+			T::Currency::unreserve(&who, anchor.deposit);
+
+			Self::deposit_event(RawEvent::Removed(who, anchor.room, hash));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The deposit required to anchor `hash` with the given license tag.
+	pub fn deposit_for(hash: &ContentHash, license: &[u8]) -> BalanceOf<T> {
+		let bytes = (hash.encoded_size() + license.len()) as u32;
+		T::AnchorDepositPerByte::get()
+			.saturating_mul(bytes.into())
+			.saturating_add(T::AnchorDepositBase::get())
+	}
+
+	/// All anchors of `room`.
+	pub fn anchors_by_room(
+		room: T::RoomId,
+	) -> Vec<(ContentHash, Anchor<T::AccountId, T::RoomId, BalanceOf<T>, T::BlockNumber>)> {
+		RoomAnchors::<T>::iter_prefix(room)
+			.filter_map(|(hash, ())| Anchors::<T>::get(&hash).map(|anchor| (hash, anchor)))
+			.collect()
+	}
+}
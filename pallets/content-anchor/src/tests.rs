@@ -0,0 +1,93 @@
+use crate::{Error, ContentHash, mock::*};
+use frame_support::{assert_ok, assert_noop};
+
+fn digest(byte: u8) -> ContentHash {
+	ContentHash::Blake2_256([byte; 32])
+}
+
+#[test]
+fn anchor_reserves_deposit_by_size() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 7, digest(1), 1024, b"CC-BY".to_vec()));
+
+		let anchor = ContentAnchor::anchor_of(digest(1)).unwrap();
+		assert_eq!(anchor.owner, 1);
+		assert_eq!(anchor.room, 7);
+		assert_eq!(anchor.size, 1024);
+		assert_eq!(anchor.anchored_at, 1);
+		// Base deposit plus one unit per byte of the encoded hash (1 + 32) and license (5).
+		assert_eq!(anchor.deposit, 10 + 33 + 5);
+		assert_eq!(Balances::reserved_balance(1), 48);
+	});
+}
+
+#[test]
+fn content_can_only_be_anchored_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 7, digest(1), 1, vec![]));
+		assert_noop!(
+			ContentAnchor::anchor(Origin::signed(2), 8, digest(1), 1, vec![]),
+			Error::<Test>::AlreadyAnchored
+		);
+	});
+}
+
+#[test]
+fn anchor_checks_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ContentAnchor::anchor(Origin::signed(1), 7, ContentHash::Cid(vec![0; 65]), 1, vec![]),
+			Error::<Test>::CidTooLong
+		);
+		assert_noop!(
+			ContentAnchor::anchor(Origin::signed(1), 7, digest(1), 1, vec![0; 17]),
+			Error::<Test>::LicenseTooLong
+		);
+
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 7, digest(1), 1, vec![]));
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 7, digest(2), 1, vec![]));
+		assert_noop!(
+			ContentAnchor::anchor(Origin::signed(1), 7, digest(3), 1, vec![]),
+			Error::<Test>::TooManyAnchors
+		);
+	});
+}
+
+#[test]
+fn only_owner_can_remove() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 7, digest(1), 1, vec![]));
+		assert_noop!(ContentAnchor::remove(Origin::signed(2), digest(1)), Error::<Test>::NotOwner);
+		assert_noop!(ContentAnchor::remove(Origin::signed(1), digest(2)), Error::<Test>::NotAnchored);
+
+		assert_ok!(ContentAnchor::remove(Origin::signed(1), digest(1)));
+		assert_eq!(ContentAnchor::anchor_of(digest(1)), None);
+		assert_eq!(ContentAnchor::room_anchor_count(7), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn anchors_can_be_listed_by_room() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 7, digest(1), 1, vec![]));
+		assert_ok!(ContentAnchor::anchor(Origin::signed(2), 7, digest(2), 2, vec![]));
+		assert_ok!(ContentAnchor::anchor(Origin::signed(1), 8, digest(3), 3, vec![]));
+
+		let mut sizes: Vec<_> = ContentAnchor::anchors_by_room(7)
+			.into_iter()
+			.map(|(_, anchor)| anchor.size)
+			.collect();
+		sizes.sort();
+		assert_eq!(sizes, vec![1, 2]);
+		assert_eq!(ContentAnchor::anchored_rooms(), 2);
+
+		assert_ok!(ContentAnchor::remove(Origin::signed(1), digest(1)));
+		assert_eq!(ContentAnchor::anchors_by_room(7).len(), 1);
+		assert!(ContentAnchor::anchors_by_room(9).is_empty());
+		assert_eq!(ContentAnchor::anchored_rooms(), 2);
+
+		assert_ok!(ContentAnchor::remove(Origin::signed(1), digest(3)));
+		assert_eq!(ContentAnchor::anchored_rooms(), 1);
+	});
+}
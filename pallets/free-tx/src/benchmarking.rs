@@ -0,0 +1,45 @@
+//! Benchmarking setup for pallet-free-tx
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::OnFinalize;
+
+use crate::Module as FreeTx;
+
+benchmarks! {
+	on_finalize {
+		FreeCallsInBlock::put(T::MaxFreeCallsPerBlock::get());
+	}: {
+		FreeTx::<T>::on_finalize(Zero::zero());
+	}
+	verify {
+		assert_eq!(FreeTx::<T>::free_calls_in_block(), 0);
+	}
+
+	free_call {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance());
+	}: {
+		assert!(FreeTx::<T>::has_free_calls(&caller));
+		FreeTx::<T>::use_free_call(&caller)?;
+	}
+	verify {
+		assert_eq!(FreeTx::<T>::free_calls_in_block(), 1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_on_finalize::<Test>());
+			assert_ok!(test_benchmark_free_call::<Test>());
+		});
+	}
+}
@@ -0,0 +1,291 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Fee-free transactions for new users.
+//!
+//! Every account holding at least the existential deposit may dispatch up to
+//! `FreeCallsPerPeriod` whitelisted calls per `QuotaPeriod` without paying transaction fees, so
+//! that a user who was sent a few tokens can join a room without spending them. Accounts that do
+//! not exist get no quota: every fresh key would otherwise come with free calls, and the nonce of
+//! its first call would create an account nobody paid a deposit for.
+//!
+//! The quota is applied by the `ChargeTransactionPaymentOrFree` signed extension, which takes the
+//! place of `pallet_transaction_payment::ChargeTransactionPayment` in the runtime's `SignedExtra`.
+//! Calls that are not whitelisted, or that exceed the quota, are charged exactly as before, and so
+//! are tipped calls: a tip is paid in full along with the fee rather than dropped.
+//!
+//! To keep free transactions from crowding out paying ones, they are validated with the lowest
+//! priority, expire from the pool at the end of the quota period, and at most
+//! `MaxFreeCallsPerBlock` of them are included in a block; the rest wait for a later block.
+//! Every free transaction of an account provides the same tag until one of them is included, so
+//! the pool holds at most one pending free transaction per account rather than any number of
+//! them against a quota that is only checked on chain.
+//!
+//! The bookkeeping of a free call is added to the weight of its block, as no fee pays for it.
+//!
+//! The usage of reaped accounts is dropped through the pallet's `OnKilledAccount` implementation,
+//! with a `UsageCleared` event. A new account gets a fresh quota either way, so nothing is gained
+//! by being reaped.
+
+use codec::{Compact, Decode, Encode};
+use frame_support::{
+	decl_event, decl_module, decl_storage,
+	traits::{Currency, Filter, Get, OnKilledAccount},
+	weights::{DispatchInfo, PostDispatchInfo, Weight},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{
+	traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionLongevity, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
+	},
+	DispatchResult, SaturatedConversion,
+};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Prefix of the tags provided by free transactions.
+const FREE_CALL_TAG: &[u8] = b"free-tx";
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The currency whose existential deposit an account must hold to get free calls.
+	type Currency: Currency<Self::AccountId>;
+	/// Calls that may be dispatched without fees while the signer has quota left.
+	type FreeCalls: Filter<<Self as frame_system::Config>::Call>;
+	/// Number of free calls every account may make per quota period.
+	type FreeCallsPerPeriod: Get<u32>;
+	/// Length of a quota period in blocks.
+	type QuotaPeriod: Get<Self::BlockNumber>;
+	/// Maximum number of free calls included in a single block.
+	type MaxFreeCallsPerBlock: Get<u32>;
+	/// Weight information for the hooks and free calls of this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as FreeTx {
+		/// The quota period an account last made a free call in, and how many free calls it made
+		/// in that period.
+		Usage get(fn usage): map hasher(blake2_128_concat) T::AccountId => (T::BlockNumber, u32);
+
+		/// Number of free calls included in the current block.
+		FreeCallsInBlock get(fn free_calls_in_block): u32;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
+		/// The quota usage of a reaped account was dropped. [who]
+		UsageCleared(AccountId),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		const FreeCallsPerPeriod: u32 = T::FreeCallsPerPeriod::get();
+		const QuotaPeriod: T::BlockNumber = T::QuotaPeriod::get();
+		const MaxFreeCallsPerBlock: u32 = T::MaxFreeCallsPerBlock::get();
+
+		fn deposit_event() = default;
+
+		fn on_initialize() -> Weight {
+			T::WeightInfo::on_finalize()
+		}
+
+		fn on_finalize() {
+			FreeCallsInBlock::kill();
+		}
+	}
+}
+
+/// Forgets the quota usage of reaped accounts.
+impl<T: Config> OnKilledAccount<T::AccountId> for Module<T> {
+	fn on_killed_account(who: &T::AccountId) {
+		if Usage::<T>::contains_key(who) {
+			Usage::<T>::remove(who);
+			Self::deposit_event(RawEvent::UsageCleared(who.clone()));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The index of the current quota period.
+	fn current_period() -> T::BlockNumber {
+		frame_system::Module::<T>::block_number() / T::QuotaPeriod::get()
+	}
+
+	/// Number of free calls `who` has left in the current quota period.
+	pub fn remaining_free_calls(who: &T::AccountId) -> u32 {
+		let (period, used) = Usage::<T>::get(who);
+		if period == Self::current_period() {
+			T::FreeCallsPerPeriod::get().saturating_sub(used)
+		} else {
+			T::FreeCallsPerPeriod::get()
+		}
+	}
+
+	/// Whether `who` may dispatch `call` without fees.
+	pub fn is_free(who: &T::AccountId, call: &<T as frame_system::Config>::Call) -> bool {
+		T::FreeCalls::filter(call) && Self::has_free_calls(who)
+	}
+
+	/// Whether `who` holds the existential deposit and has free calls left.
+	pub fn has_free_calls(who: &T::AccountId) -> bool {
+		T::Currency::total_balance(who) >= T::Currency::minimum_balance() &&
+			!Self::remaining_free_calls(who).is_zero()
+	}
+
+	/// The tag provided by a free transaction of `who`: the account, the quota period and the
+	/// number of free calls already used in it.
+	fn free_call_tag(who: &T::AccountId) -> Vec<u8> {
+		let used = T::FreeCallsPerPeriod::get() - Self::remaining_free_calls(who);
+		(FREE_CALL_TAG, who, Self::current_period(), used).encode()
+	}
+
+	/// Number of blocks until the current quota period ends.
+	fn blocks_left_in_period() -> T::BlockNumber {
+		let period = T::QuotaPeriod::get();
+		period - frame_system::Module::<T>::block_number() % period
+	}
+
+	/// Record a free call of `who` in the current block.
+	fn use_free_call(who: &T::AccountId) -> Result<(), TransactionValidityError> {
+		if FreeCallsInBlock::get() >= T::MaxFreeCallsPerBlock::get() {
+			// Not invalid, the transaction just has to wait for a block with room for it.
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+		FreeCallsInBlock::mutate(|count| *count += 1);
+
+		let current = Self::current_period();
+		Usage::<T>::mutate(who, |(period, used)| {
+			if *period != current {
+				*period = current;
+				*used = 0;
+			}
+			*used += 1;
+		});
+		Ok(())
+	}
+}
+
+/// Drop-in replacement for `ChargeTransactionPayment` that lets whitelisted calls through without
+/// fees while the signer has free calls left.
+///
+/// It encodes exactly like `ChargeTransactionPayment` (a compact tip) and keeps its identifier, so
+/// existing wallets can build transactions for it unchanged.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeTransactionPaymentOrFree<T: Config>(ChargeTransactionPayment<T>);
+
+impl<T: Config> ChargeTransactionPaymentOrFree<T> {
+	/// Whether the transaction carries a tip.
+	fn is_tipped(&self) -> bool {
+		// `ChargeTransactionPayment` encodes as its compact tip and has no getter for it.
+		Compact::<u128>::decode(&mut &self.0.encode()[..]).map_or(true, |tip| tip.0 != 0)
+	}
+
+	/// Whether `who` may dispatch `call` without fees in this transaction.
+	fn is_free(&self, who: &T::AccountId, call: &<T as frame_system::Config>::Call) -> bool {
+		!self.is_tipped() && Module::<T>::is_free(who, call)
+	}
+}
+
+impl<T: Config> From<ChargeTransactionPayment<T>> for ChargeTransactionPaymentOrFree<T> {
+	fn from(inner: ChargeTransactionPayment<T>) -> Self {
+		Self(inner)
+	}
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeTransactionPaymentOrFree<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeTransactionPaymentOrFree<{:?}>", self.0)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for ChargeTransactionPaymentOrFree<T> where
+	<T as frame_system::Config>::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+	ChargeTransactionPayment<T>: SignedExtension<
+		AccountId=T::AccountId,
+		Call=<T as frame_system::Config>::Call,
+		AdditionalSigned=(),
+	>,
+{
+	const IDENTIFIER: &'static str = "ChargeTransactionPayment";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	/// `None` if the call was free, otherwise what `ChargeTransactionPayment` needs to refund.
+	type Pre = Option<<ChargeTransactionPayment<T> as SignedExtension>::Pre>;
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		if self.is_free(who, call) {
+			Ok(ValidTransaction {
+				priority: 0,
+				provides: vec![Module::<T>::free_call_tag(who)],
+				longevity: Module::<T>::blocks_left_in_period()
+					.saturated_into::<TransactionLongevity>(),
+				..Default::default()
+			})
+		} else {
+			self.0.validate(who, call, info, len)
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		if self.is_free(who, call) {
+			Module::<T>::use_free_call(who)?;
+			frame_system::Module::<T>::register_extra_weight_unchecked(
+				T::WeightInfo::free_call(),
+				info.class,
+			);
+			Ok(None)
+		} else {
+			self.0.pre_dispatch(who, call, info, len).map(Some)
+		}
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		match pre {
+			Some(pre) => ChargeTransactionPayment::<T>::post_dispatch(pre, info, post_info, len, result),
+			None => Ok(()),
+		}
+	}
+}
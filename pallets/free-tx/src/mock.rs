@@ -0,0 +1,122 @@
+use crate as pallet_free_tx;
+use sp_core::H256;
+use frame_support::{parameter_types, traits::Filter, weights::IdentityFee};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use frame_system as system;
+use pallet_transaction_payment::CurrencyAdapter;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		FreeTx: pallet_free_tx::{Module, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = FreeTx;
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const TransactionByteFee: u64 = 1;
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+/// `System::remark` stands in for the room calls the runtime whitelists.
+pub struct FreeCalls;
+impl Filter<Call> for FreeCalls {
+	fn filter(call: &Call) -> bool {
+		matches!(call, Call::System(frame_system::Call::remark(..)))
+	}
+}
+
+parameter_types! {
+	pub const FreeCallsPerPeriod: u32 = 2;
+	pub const QuotaPeriod: u64 = 10;
+	pub const MaxFreeCallsPerBlock: u32 = 3;
+}
+
+impl pallet_free_tx::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type FreeCalls = FreeCalls;
+	type FreeCallsPerPeriod = FreeCallsPerPeriod;
+	type QuotaPeriod = QuotaPeriod;
+	type MaxFreeCallsPerBlock = MaxFreeCallsPerBlock;
+	type WeightInfo = ();
+}
+
+/// An account holding just the existential deposit.
+pub const NEWCOMER: u64 = 1;
+/// An account that can pay for its transactions.
+pub const RICH: u64 = 2;
+/// An account that does not exist.
+pub const STRANGER: u64 = 7;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(NEWCOMER, 1), (RICH, 1 << 40), (3, 1), (4, 1), (5, 1), (6, 1)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
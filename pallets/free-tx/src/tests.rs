@@ -0,0 +1,198 @@
+use crate::{ChargeTransactionPaymentOrFree, RawEvent, WeightInfo, mock::*};
+use frame_support::{
+	assert_ok,
+	traits::OnFinalize,
+	weights::{DispatchClass, DispatchInfo, GetDispatchInfo},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+
+fn remark() -> Call {
+	Call::System(frame_system::Call::remark(vec![]))
+}
+
+fn transfer() -> Call {
+	Call::Balances(pallet_balances::Call::transfer(RICH, 1))
+}
+
+fn extension() -> ChargeTransactionPaymentOrFree<Test> {
+	ChargeTransactionPayment::<Test>::from(0).into()
+}
+
+fn pre_dispatch(who: u64, call: &Call) -> Result<bool, TransactionValidityError> {
+	let info: DispatchInfo = call.get_dispatch_info();
+	extension().pre_dispatch(&who, call, &info, 10).map(|pre| pre.is_none())
+}
+
+fn next_block() {
+	FreeTx::on_finalize(System::block_number());
+	System::set_block_number(System::block_number() + 1);
+}
+
+#[test]
+fn whitelisted_calls_are_free_within_quota() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert_ok!(extension().validate(&NEWCOMER, &call, &call.get_dispatch_info(), 10));
+		assert_eq!(pre_dispatch(NEWCOMER, &call), Ok(true));
+		assert_eq!(FreeTx::remaining_free_calls(&NEWCOMER), 1);
+		assert_eq!(pre_dispatch(NEWCOMER, &call), Ok(true));
+		assert_eq!(FreeTx::remaining_free_calls(&NEWCOMER), 0);
+
+		// Out of quota and without funds the transaction cannot pay.
+		assert_eq!(
+			extension().validate(&NEWCOMER, &call, &call.get_dispatch_info(), 10),
+			Err(InvalidTransaction::Payment.into()),
+		);
+	});
+}
+
+#[test]
+fn free_calls_add_their_bookkeeping_to_the_block_weight() {
+	new_test_ext().execute_with(|| {
+		let normal = || *System::block_weight().get(DispatchClass::Normal);
+		assert_eq!(pre_dispatch(NEWCOMER, &remark()), Ok(true));
+		assert_eq!(normal(), <() as WeightInfo>::free_call());
+
+		// Paid calls are covered by their fee.
+		assert_eq!(pre_dispatch(RICH, &transfer()), Ok(false));
+		assert_eq!(normal(), <() as WeightInfo>::free_call());
+	});
+}
+
+#[test]
+fn calls_beyond_quota_are_charged() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert_eq!(pre_dispatch(RICH, &call), Ok(true));
+		assert_eq!(pre_dispatch(RICH, &call), Ok(true));
+
+		let balance = Balances::free_balance(RICH);
+		assert_eq!(pre_dispatch(RICH, &call), Ok(false));
+		assert!(Balances::free_balance(RICH) < balance);
+	});
+}
+
+#[test]
+fn tipped_calls_are_charged_with_their_tip() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		let info: DispatchInfo = call.get_dispatch_info();
+		let tipped: ChargeTransactionPaymentOrFree<Test> = ChargeTransactionPayment::from(5).into();
+		let balance = Balances::free_balance(RICH);
+		assert!(tipped.pre_dispatch(&RICH, &call, &info, 10).unwrap().is_some());
+		assert!(Balances::free_balance(RICH) < balance - 5);
+		assert_eq!(FreeTx::remaining_free_calls(&RICH), 2);
+	});
+}
+
+#[test]
+fn other_calls_are_charged() {
+	new_test_ext().execute_with(|| {
+		let call = transfer();
+		let balance = Balances::free_balance(RICH);
+		assert_eq!(pre_dispatch(RICH, &call), Ok(false));
+		assert!(Balances::free_balance(RICH) < balance);
+		assert_eq!(FreeTx::remaining_free_calls(&RICH), 2);
+
+		assert_eq!(
+			extension().validate(&NEWCOMER, &call, &call.get_dispatch_info(), 10),
+			Err(InvalidTransaction::Payment.into()),
+		);
+	});
+}
+
+#[test]
+fn quota_resets_every_period() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert_eq!(pre_dispatch(NEWCOMER, &call), Ok(true));
+		assert_eq!(pre_dispatch(NEWCOMER, &call), Ok(true));
+		assert_eq!(FreeTx::remaining_free_calls(&NEWCOMER), 0);
+
+		while System::block_number() < QuotaPeriod::get() {
+			next_block();
+		}
+		assert_eq!(FreeTx::remaining_free_calls(&NEWCOMER), 2);
+		assert_eq!(pre_dispatch(NEWCOMER, &call), Ok(true));
+	});
+}
+
+#[test]
+fn usage_of_reaped_accounts_is_dropped() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(pre_dispatch(RICH, &remark()), Ok(true));
+		assert!(crate::Usage::<Test>::contains_key(RICH));
+
+		assert_ok!(Balances::transfer(Origin::signed(RICH), NEWCOMER, 1 << 40));
+		assert!(!crate::Usage::<Test>::contains_key(RICH));
+		assert_eq!(FreeTx::remaining_free_calls(&RICH), 2);
+		assert!(System::events().iter().any(|record| {
+			record.event == Event::pallet_free_tx(RawEvent::UsageCleared(RICH))
+		}));
+	});
+}
+
+#[test]
+fn free_calls_per_block_are_capped() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert_eq!(pre_dispatch(3, &call), Ok(true));
+		assert_eq!(pre_dispatch(4, &call), Ok(true));
+		assert_eq!(pre_dispatch(5, &call), Ok(true));
+		assert_eq!(pre_dispatch(6, &call), Err(InvalidTransaction::ExhaustsResources.into()));
+		assert_eq!(FreeTx::remaining_free_calls(&6), 2);
+
+		next_block();
+		assert_eq!(pre_dispatch(6, &call), Ok(true));
+	});
+}
+
+#[test]
+fn free_calls_have_lowest_priority_and_expire_with_the_period() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		let valid = extension().validate(&NEWCOMER, &call, &call.get_dispatch_info(), 10).unwrap();
+		assert_eq!(valid.priority, 0);
+		assert_eq!(valid.longevity, QuotaPeriod::get() - 1);
+	});
+}
+
+#[test]
+fn accounts_below_the_existential_deposit_get_no_free_calls() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert!(!FreeTx::is_free(&STRANGER, &call));
+		assert_eq!(
+			extension().validate(&STRANGER, &call, &call.get_dispatch_info(), 10),
+			Err(InvalidTransaction::Payment.into()),
+		);
+		assert_eq!(pre_dispatch(STRANGER, &call), Err(InvalidTransaction::Payment.into()));
+		assert!(!System::account_exists(&STRANGER));
+	});
+}
+
+#[test]
+fn pending_free_calls_of_an_account_share_a_tag() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		let validate = |who| {
+			extension().validate(&who, &call, &call.get_dispatch_info(), 10).unwrap().provides
+		};
+
+		// However many are submitted, the pool keeps one free transaction per account...
+		let tag = validate(NEWCOMER);
+		assert_eq!(tag.len(), 1);
+		for _ in 0..FreeCallsPerPeriod::get() + 1 {
+			assert_eq!(validate(NEWCOMER), tag);
+		}
+		assert_ne!(validate(3), tag);
+
+		// ...until it is included, and the next one takes the next slot of the quota.
+		assert_eq!(pre_dispatch(NEWCOMER, &call), Ok(true));
+		assert_ne!(validate(NEWCOMER), tag);
+	});
+}
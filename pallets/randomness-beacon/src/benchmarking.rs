@@ -0,0 +1,73 @@
+//! Benchmarking setup for pallet-randomness-beacon
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use sp_runtime::traits::Bounded;
+
+use crate::Module as RandomnessBeacon;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who = account(name, index, 0);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value());
+	who
+}
+
+benchmarks! {
+	on_initialize {
+		let c in 0 .. T::MaxCommitmentsPerBlock::get();
+		for i in 0 .. c {
+			let who = funded_account::<T>("committer", i);
+			let commitment = RandomnessBeacon::<T>::commitment_of(&who, &[0u8; 32]);
+			RandomnessBeacon::<T>::commit(RawOrigin::Signed(who).into(), commitment)?;
+		}
+		let expires_at = frame_system::Module::<T>::block_number()
+			+ T::RevealPeriod::get() + One::one();
+	}: {
+		RandomnessBeacon::<T>::on_initialize(expires_at);
+	}
+	verify {
+		assert_eq!(Expiring::<T>::decode_len(expires_at), None);
+	}
+
+	commit {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let commitment = RandomnessBeacon::<T>::commitment_of(&caller, &[1u8; 32]);
+	}: _(RawOrigin::Signed(caller.clone()), commitment)
+	verify {
+		assert!(Commitments::<T>::contains_key(&caller));
+	}
+
+	reveal {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let secret = [1u8; 32];
+		let commitment = RandomnessBeacon::<T>::commitment_of(&caller, &secret);
+		RandomnessBeacon::<T>::commit(RawOrigin::Signed(caller.clone()).into(), commitment)?;
+		frame_system::Module::<T>::set_block_number(
+			frame_system::Module::<T>::block_number() + One::one()
+		);
+	}: _(RawOrigin::Signed(caller.clone()), secret)
+	verify {
+		assert!(!Commitments::<T>::contains_key(&caller));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_on_initialize::<Test>());
+			assert_ok!(test_benchmark_commit::<Test>());
+			assert_ok!(test_benchmark_reveal::<Test>());
+		});
+	}
+}
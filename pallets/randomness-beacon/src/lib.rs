@@ -0,0 +1,253 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A commit-reveal randomness beacon.
+//!
+//! `RandomnessCollectiveFlip` only mixes parent block hashes, which the block author chooses.
+//! This pallet instead mixes secrets that any account can contribute in two phases:
+//!
+//! 1. `commit` a hash of `(account, secret)` together with a deposit;
+//! 2. `reveal` the secret in a later block, within `RevealPeriod` blocks of the commitment.
+//!
+//! Every reveal is folded into the beacon seed. A commitment that is not revealed in time forfeits
+//! its deposit, which makes withholding an unfavourable secret costly; it is not free of bias,
+//! as the last account to reveal can still trade its deposit for not revealing.
+//!
+//! Randomness is only derived from the seed as it was at the end of the previous block, so a
+//! reveal cannot change the outcome of calls in its own block, whose order the block author
+//! picks. There is no seed before the first reveal: `try_random` returns `None` until then.
+//!
+//! The pallet implements `Randomness<T::Hash>`, so other pallets consume it through their
+//! `Config` (e.g. `type Randomness = RandomnessBeacon;` in the runtime) rather than reading block
+//! hashes or calling `random_seed()` directly.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+	traits::{Currency, Get, Imbalance, OnUnbalanced, Randomness, ReservableCurrency},
+	weights::Weight,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{
+	traits::{Hash as HashT, One, Saturating},
+	RuntimeDebug,
+};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// A pending commitment of an account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Commitment<Hash, BlockNumber, Balance> {
+	/// Hash of `(account, secret)`.
+	pub hash: Hash,
+	/// The block in which the commitment was made.
+	pub committed_at: BlockNumber,
+	/// The deposit reserved until the secret is revealed.
+	pub deposit: Balance,
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The currency in which commitment deposits are reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// The deposit reserved for every commitment.
+	type CommitDeposit: Get<BalanceOf<Self>>;
+	/// Number of blocks after a commitment during which its secret can be revealed.
+	type RevealPeriod: Get<Self::BlockNumber>;
+	/// Maximum number of commitments expiring in the same block.
+	type MaxCommitmentsPerBlock: Get<u32>;
+	/// Handler for the deposits of commitments that were never revealed.
+	type Forfeited: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as RandomnessBeacon {
+		/// The current seed, updated by every reveal.
+		Seed get(fn seed): T::Hash;
+
+		/// The seed randomness is derived from: `Seed` as of the end of the previous block, or
+		/// `None` before the first reveal.
+		ServedSeed get(fn served_seed): Option<T::Hash>;
+
+		/// Pending commitments by account.
+		Commitments get(fn commitments):
+			map hasher(blake2_128_concat) T::AccountId
+			=> Option<Commitment<T::Hash, T::BlockNumber, BalanceOf<T>>>;
+
+		/// Accounts whose commitment expires at the start of a given block.
+		Expiring: map hasher(twox_64_concat) T::BlockNumber => Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Hash = <T as frame_system::Config>::Hash,
+		Balance = BalanceOf<T>,
+	{
+		/// An account committed to a secret. [who, commitment]
+		Committed(AccountId, Hash),
+		/// An account revealed its secret and the seed was updated. [who, new_seed]
+		Revealed(AccountId, Hash),
+		/// A commitment expired without being revealed. [who, slashed_deposit]
+		Forfeited(AccountId, Balance),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The account already has a pending commitment.
+		AlreadyCommitted,
+		/// The account has no pending commitment.
+		NoCommitment,
+		/// A secret cannot be revealed in the block it was committed in.
+		RevealTooEarly,
+		/// The revealed secret does not match the commitment.
+		InvalidReveal,
+		/// Too many commitments already expire in the same block.
+		TooManyCommitments,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		const CommitDeposit: BalanceOf<T> = T::CommitDeposit::get();
+		const RevealPeriod: T::BlockNumber = T::RevealPeriod::get();
+		const MaxCommitmentsPerBlock: u32 = T::MaxCommitmentsPerBlock::get();
+
+		fn deposit_event() = default;
+
+		/// Serve the seed of the previous block and forfeit the deposits of the commitments that
+		/// were not revealed in time.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let seed = Self::seed();
+			if seed != T::Hash::default() && Self::served_seed() != Some(seed) {
+				ServedSeed::<T>::put(seed);
+			}
+
+			let expiring = Expiring::<T>::take(now);
+			let count = expiring.len() as u32;
+			let committed_at = now.saturating_sub(T::RevealPeriod::get() + One::one());
+			for who in expiring {
+				match Commitments::<T>::get(&who) {
+					// The account may have revealed and committed again in the meantime.
+					Some(commitment) if commitment.committed_at == committed_at => {
+						Commitments::<T>::remove(&who);
+						let (imbalance, _) = T::Currency::slash_reserved(&who, commitment.deposit);
+						let slashed = imbalance.peek();
+						T::Forfeited::on_unbalanced(imbalance);
+						Self::deposit_event(RawEvent::Forfeited(who, slashed));
+					},
+					_ => {},
+				}
+			}
+			T::WeightInfo::on_initialize(count)
+		}
+
+		/// Commit to a secret by submitting `hash((origin, secret))`.
+		///
+		/// The secret must be revealed with `reveal` within `RevealPeriod` blocks, otherwise the
+		/// `CommitDeposit` is forfeited.
+		#[weight = T::WeightInfo::commit()]
+		pub fn commit(origin, commitment: T::Hash) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Commitments::<T>::contains_key(&who), Error::<T>::AlreadyCommitted);
+
+			let now = frame_system::Module::<T>::block_number();
+			let expires_at = now + T::RevealPeriod::get() + One::one();
+			ensure!(
+				(Expiring::<T>::decode_len(expires_at).unwrap_or(0) as u32)
+					< T::MaxCommitmentsPerBlock::get(),
+				Error::<T>::TooManyCommitments,
+			);
+
+			let deposit = T::CommitDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+
+			Commitments::<T>::insert(&who, Commitment {
+				hash: commitment,
+				committed_at: now,
+				deposit,
+			});
+			Expiring::<T>::append(expires_at, who.clone());
+
+			Self::deposit_event(RawEvent::Committed(who, commitment));
+			Ok(())
+		}
+
+		/// Reveal the secret of a pending commitment, mixing it into the seed and releasing the
+		/// deposit.
+		#[weight = T::WeightInfo::reveal()]
+		pub fn reveal(origin, secret: [u8; 32]) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			let commitment = Commitments::<T>::get(&who).ok_or(Error::<T>::NoCommitment)?;
+
+			let now = frame_system::Module::<T>::block_number();
+			ensure!(now > commitment.committed_at, Error::<T>::RevealTooEarly);
+			ensure!(
+				Self::commitment_of(&who, &secret) == commitment.hash,
+				Error::<T>::InvalidReveal,
+			);
+
+			Commitments::<T>::remove(&who);
+			T::Currency::unreserve(&who, commitment.deposit);
+
+			let seed = T::Hashing::hash_of(&(Self::seed(), secret));
+			Seed::<T>::put(seed);
+
+			Self::deposit_event(RawEvent::Revealed(who, seed));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The commitment `who` must submit for `secret`.
+	///
+	/// The account is part of the preimage so that a commitment cannot be copied and revealed by
+	/// somebody else.
+	pub fn commitment_of(who: &T::AccountId, secret: &[u8; 32]) -> T::Hash {
+		T::Hashing::hash_of(&(who, secret))
+	}
+
+	/// Derive a random value for `subject` from the served seed, or `None` if nothing was
+	/// revealed before the current block.
+	///
+	/// Two calls with the same subject in the same block return the same value; callers should
+	/// include something unique (e.g. an id and a nonce) in the subject.
+	pub fn try_random(subject: &[u8]) -> Option<T::Hash> {
+		Self::served_seed().map(|seed| T::Hashing::hash_of(&(seed, subject)))
+	}
+}
+
+impl<T: Config> Randomness<T::Hash> for Module<T> {
+	/// Like `try_random`, but derives the value from the subject alone before the first reveal.
+	///
+	/// Such a value is known to everybody in advance; consumers that cannot accept that should
+	/// use `try_random` and wait for a seed.
+	fn random(subject: &[u8]) -> T::Hash {
+		Self::try_random(subject)
+			.unwrap_or_else(|| T::Hashing::hash_of(&(T::Hash::default(), subject)))
+	}
+}
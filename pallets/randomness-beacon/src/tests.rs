@@ -0,0 +1,177 @@
+use crate::{Error, Commitments, RawEvent, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::{Randomness, ReservableCurrency}};
+
+#[test]
+fn commit_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let commitment = RandomnessBeacon::commitment_of(&1, &[7; 32]);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(1), commitment));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_noop!(
+			RandomnessBeacon::commit(Origin::signed(1), commitment),
+			Error::<Test>::AlreadyCommitted
+		);
+	});
+}
+
+#[test]
+fn reveal_updates_seed_and_releases_deposit() {
+	new_test_ext().execute_with(|| {
+		let seed = RandomnessBeacon::seed();
+		let commitment = RandomnessBeacon::commitment_of(&1, &[7; 32]);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(1), commitment));
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(1), [7; 32]),
+			Error::<Test>::RevealTooEarly
+		);
+
+		run_to_block(2);
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(1), [8; 32]),
+			Error::<Test>::InvalidReveal
+		);
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(1), [7; 32]));
+		assert_ne!(RandomnessBeacon::seed(), seed);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+fn commitment_cannot_be_revealed_by_another_account() {
+	new_test_ext().execute_with(|| {
+		let commitment = RandomnessBeacon::commitment_of(&1, &[7; 32]);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(2), commitment));
+		run_to_block(2);
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(2), [7; 32]),
+			Error::<Test>::InvalidReveal
+		);
+	});
+}
+
+#[test]
+fn unrevealed_commitment_forfeits_deposit() {
+	new_test_ext().execute_with(|| {
+		let commitment = RandomnessBeacon::commitment_of(&1, &[7; 32]);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(1), commitment));
+
+		// Still revealable in the last block of the reveal period.
+		run_to_block(1 + RevealPeriod::get());
+		assert!(Commitments::<Test>::contains_key(1));
+
+		run_to_block(2 + RevealPeriod::get());
+		assert!(!Commitments::<Test>::contains_key(1));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 90);
+	});
+}
+
+#[test]
+fn recommitting_after_reveal_is_not_forfeited_early() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(RandomnessBeacon::commit(
+			Origin::signed(1),
+			RandomnessBeacon::commitment_of(&1, &[7; 32]),
+		));
+		run_to_block(2);
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(1), [7; 32]));
+		assert_ok!(RandomnessBeacon::commit(
+			Origin::signed(1),
+			RandomnessBeacon::commitment_of(&1, &[9; 32]),
+		));
+
+		// The first commitment's expiry must leave the second one alone.
+		run_to_block(2 + RevealPeriod::get());
+		assert!(Commitments::<Test>::contains_key(1));
+		assert_eq!(Balances::reserved_balance(1), 10);
+	});
+}
+
+#[test]
+fn commitments_per_block_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			assert_ok!(RandomnessBeacon::commit(Origin::signed(who), Default::default()));
+		}
+		assert_noop!(
+			RandomnessBeacon::commit(Origin::signed(4), Default::default()),
+			Error::<Test>::TooManyCommitments
+		);
+	});
+}
+
+#[test]
+fn randomness_depends_on_subject_and_seed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(RandomnessBeacon::commit(
+			Origin::signed(1),
+			RandomnessBeacon::commitment_of(&1, &[7; 32]),
+		));
+		run_to_block(2);
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(1), [7; 32]));
+		run_to_block(3);
+
+		let raffle = RandomnessBeacon::try_random(b"raffle").unwrap();
+		assert_ne!(raffle, RandomnessBeacon::try_random(b"lottery").unwrap());
+		assert_eq!(RandomnessBeacon::random(b"raffle"), raffle);
+
+		assert_ok!(RandomnessBeacon::commit(
+			Origin::signed(2),
+			RandomnessBeacon::commitment_of(&2, &[8; 32]),
+		));
+		run_to_block(4);
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(2), [8; 32]));
+		run_to_block(5);
+		assert_ne!(RandomnessBeacon::try_random(b"raffle").unwrap(), raffle);
+	});
+}
+
+#[test]
+fn no_randomness_is_served_before_the_first_reveal() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(RandomnessBeacon::try_random(b"raffle"), None);
+		assert_ok!(RandomnessBeacon::commit(
+			Origin::signed(1),
+			RandomnessBeacon::commitment_of(&1, &[7; 32]),
+		));
+		run_to_block(2);
+		assert_eq!(RandomnessBeacon::try_random(b"raffle"), None);
+	});
+}
+
+#[test]
+fn reveals_only_affect_later_blocks() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=2 {
+			assert_ok!(RandomnessBeacon::commit(
+				Origin::signed(who),
+				RandomnessBeacon::commitment_of(&who, &[who as u8; 32]),
+			));
+		}
+		run_to_block(2);
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(1), [1; 32]));
+		run_to_block(3);
+
+		let before = RandomnessBeacon::try_random(b"raffle");
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(2), [2; 32]));
+		assert_eq!(RandomnessBeacon::try_random(b"raffle"), before);
+		run_to_block(4);
+		assert_ne!(RandomnessBeacon::try_random(b"raffle"), before);
+	});
+}
+
+#[test]
+fn forfeited_event_reports_the_slashed_amount() {
+	new_test_ext().execute_with(|| {
+		let commitment = RandomnessBeacon::commitment_of(&1, &[7; 32]);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(1), commitment));
+		// Something else slashed part of the reserved deposit in the meantime.
+		let _ = Balances::slash_reserved(&1, 4);
+
+		run_to_block(2 + RevealPeriod::get());
+		assert!(System::events().iter().any(|record| {
+			record.event == Event::pallet_randomness_beacon(RawEvent::Forfeited(1, 6))
+		}));
+	});
+}
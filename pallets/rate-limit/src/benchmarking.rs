@@ -0,0 +1,35 @@
+//! Benchmarking setup for pallet-rate-limit
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+
+use crate::Module as RateLimit;
+
+benchmarks! {
+	note_call {
+		let caller: T::AccountId = whitelisted_caller();
+		// An earlier call in the current window, so that the count is read and updated.
+		let calls = T::MaxCallsPerWindow::get().saturating_sub(1);
+		CallCount::<T>::insert(&caller, (RateLimit::<T>::current_window(), calls));
+	}: {
+		RateLimit::<T>::note_call(&caller)?;
+	}
+	verify {
+		assert_eq!(RateLimit::<T>::calls_in_window(&caller), T::MaxCallsPerWindow::get());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_note_call::<Test>());
+		});
+	}
+}
@@ -0,0 +1,202 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Per-account rate limiting of calls.
+//!
+//! During busy events a single account can flood the transaction pool with room actions. The
+//! `CheckRateLimit` signed extension caps every account at `MaxCallsPerWindow` rate-limited calls
+//! per window of `Window` blocks. Excess transactions are rejected by `validate_transaction`, so
+//! they never enter the pool, with `InvalidTransaction::Custom(RATE_LIMITED)` so that clients can
+//! tell the user to slow down instead of reporting a generic failure.
+//!
+//! The limit is checked against the calls included on chain. Rate-limited transactions provide no
+//! tags of their own, so several of them can wait in the pool, ordered by their nonces, and an
+//! account can make all of its calls of a window in a single block. Pending transactions beyond
+//! the limit are rejected with `RATE_LIMITED` when the block author applies them, or when the pool
+//! revalidates them after the limit is reached, and are dropped from the pool.
+//!
+//! Counting a rate-limited call is added to the weight of the block it is included in.
+//!
+//! The call counts of reaped accounts are dropped through the pallet's `OnKilledAccount`
+//! implementation, with a `UsageCleared` event.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_event, decl_module, decl_storage,
+	traits::{Filter, Get, OnKilledAccount},
+};
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// The `InvalidTransaction::Custom` code of transactions rejected by the rate limit.
+pub const RATE_LIMITED: u8 = 1;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// Calls that count towards the rate limit.
+	type LimitedCalls: Filter<<Self as frame_system::Config>::Call>;
+	/// Length of a rate limiting window in blocks.
+	type Window: Get<Self::BlockNumber>;
+	/// Maximum number of rate-limited calls per account and window.
+	type MaxCallsPerWindow: Get<u32>;
+	/// Weight information for the checks of this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as RateLimit {
+		/// The window an account last made a rate-limited call in, and how many it made in that
+		/// window.
+		CallCount get(fn call_count):
+			map hasher(blake2_128_concat) T::AccountId => (T::BlockNumber, u32);
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
+		/// The call count of a reaped account was dropped. [who]
+		UsageCleared(AccountId),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		const Window: T::BlockNumber = T::Window::get();
+		const MaxCallsPerWindow: u32 = T::MaxCallsPerWindow::get();
+
+		fn deposit_event() = default;
+	}
+}
+
+/// Forgets the call counts of reaped accounts.
+impl<T: Config> OnKilledAccount<T::AccountId> for Module<T> {
+	fn on_killed_account(who: &T::AccountId) {
+		if CallCount::<T>::contains_key(who) {
+			CallCount::<T>::remove(who);
+			Self::deposit_event(RawEvent::UsageCleared(who.clone()));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The index of the current window.
+	fn current_window() -> T::BlockNumber {
+		frame_system::Module::<T>::block_number() / T::Window::get()
+	}
+
+	/// Number of rate-limited calls `who` made in the current window.
+	pub fn calls_in_window(who: &T::AccountId) -> u32 {
+		let (window, count) = CallCount::<T>::get(who);
+		if window == Self::current_window() { count } else { 0 }
+	}
+
+	/// Ensure `who` may make another rate-limited call in the current window.
+	fn ensure_within_limit(who: &T::AccountId) -> Result<(), TransactionValidityError> {
+		if Self::calls_in_window(who) >= T::MaxCallsPerWindow::get() {
+			Err(InvalidTransaction::Custom(RATE_LIMITED).into())
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Count a rate-limited call of `who`, if it is within the limit.
+	fn note_call(who: &T::AccountId) -> Result<(), TransactionValidityError> {
+		Self::ensure_within_limit(who)?;
+		let current = Self::current_window();
+		CallCount::<T>::mutate(who, |(window, count)| {
+			if *window != current {
+				*window = current;
+				*count = 0;
+			}
+			*count += 1;
+		});
+		Ok(())
+	}
+}
+
+/// Reject rate-limited calls of accounts that used up their allowance for the current window.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckRateLimit<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckRateLimit<T> {
+	/// Create new `SignedExtension` to check the rate limit.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckRateLimit<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckRateLimit<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "CheckRateLimit")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckRateLimit<T> {
+	const IDENTIFIER: &'static str = "CheckRateLimit";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if T::LimitedCalls::filter(call) {
+			Module::<T>::ensure_within_limit(who)?;
+		}
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		if T::LimitedCalls::filter(call) {
+			Module::<T>::note_call(who)?;
+			frame_system::Module::<T>::register_extra_weight_unchecked(
+				T::WeightInfo::note_call(),
+				info.class,
+			);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,128 @@
+use crate::{CheckRateLimit, RATE_LIMITED, RawEvent, WeightInfo, mock::*};
+use frame_support::{
+	assert_ok,
+	traits::OnKilledAccount,
+	weights::{DispatchClass, GetDispatchInfo},
+};
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+
+fn remark() -> Call {
+	Call::System(frame_system::Call::remark(vec![]))
+}
+
+fn validate(who: u64, call: &Call) -> Result<(), TransactionValidityError> {
+	CheckRateLimit::<Test>::new()
+		.validate(&who, call, &call.get_dispatch_info(), 10)
+		.map(|_| ())
+}
+
+fn provides(who: u64, call: &Call) -> Vec<Vec<u8>> {
+	CheckRateLimit::<Test>::new()
+		.validate(&who, call, &call.get_dispatch_info(), 10)
+		.unwrap()
+		.provides
+}
+
+fn pre_dispatch(who: u64, call: &Call) -> Result<(), TransactionValidityError> {
+	CheckRateLimit::<Test>::new().pre_dispatch(&who, call, &call.get_dispatch_info(), 10)
+}
+
+#[test]
+fn limited_calls_are_capped_per_window() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert_ok!(pre_dispatch(1, &call));
+		assert_ok!(pre_dispatch(1, &call));
+		assert_eq!(RateLimit::calls_in_window(&1), 2);
+
+		let limited = Err(InvalidTransaction::Custom(RATE_LIMITED).into());
+		assert_eq!(validate(1, &call), limited);
+		assert_eq!(pre_dispatch(1, &call), limited);
+
+		// Other accounts are not affected.
+		assert_ok!(validate(2, &call));
+	});
+}
+
+#[test]
+fn limit_resets_with_the_window() {
+	new_test_ext().execute_with(|| {
+		let call = remark();
+		assert_ok!(pre_dispatch(1, &call));
+		assert_ok!(pre_dispatch(1, &call));
+
+		System::set_block_number(Window::get() - 1);
+		assert!(validate(1, &call).is_err());
+
+		System::set_block_number(Window::get());
+		assert_eq!(RateLimit::calls_in_window(&1), 0);
+		assert_ok!(validate(1, &call));
+		assert_ok!(pre_dispatch(1, &call));
+	});
+}
+
+#[test]
+fn other_calls_are_not_limited() {
+	new_test_ext().execute_with(|| {
+		let call = Call::System(frame_system::Call::set_heap_pages(1));
+		for _ in 0..5 {
+			assert_ok!(pre_dispatch(1, &call));
+		}
+		assert_eq!(RateLimit::calls_in_window(&1), 0);
+	});
+}
+
+#[test]
+fn counts_of_reaped_accounts_are_dropped() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(pre_dispatch(1, &remark()));
+		assert_eq!(RateLimit::calls_in_window(&1), 1);
+
+		RateLimit::on_killed_account(&1);
+		assert!(!crate::CallCount::<Test>::contains_key(1));
+		let cleared = Event::pallet_rate_limit(RawEvent::UsageCleared(1));
+		assert_eq!(System::events().last().unwrap().event, cleared);
+
+		// Accounts without a call count are reaped silently.
+		RateLimit::on_killed_account(&2);
+		assert_eq!(System::events().len(), 1);
+	});
+}
+
+#[test]
+fn pending_calls_of_an_account_do_not_replace_each_other() {
+	new_test_ext().execute_with(|| {
+		// Two calls waiting in the pool, e.g. a join and a leave with consecutive nonces, provide
+		// no tags that would make one replace the other.
+		let call = remark();
+		assert!(provides(1, &call).is_empty());
+		assert!(provides(1, &call).is_empty());
+
+		// Both are included in the same block, which uses up the window.
+		assert_ok!(pre_dispatch(1, &call));
+		assert_ok!(pre_dispatch(1, &call));
+
+		// A third pending call is rejected with the code clients look for.
+		let limited = Err(InvalidTransaction::Custom(RATE_LIMITED).into());
+		assert_eq!(validate(1, &call), limited);
+		assert_eq!(pre_dispatch(1, &call), limited);
+	});
+}
+
+#[test]
+fn counted_calls_add_to_the_block_weight() {
+	new_test_ext().execute_with(|| {
+		let normal = || *System::block_weight().get(DispatchClass::Normal);
+		let call = remark();
+		assert_ok!(pre_dispatch(1, &call));
+		assert_ok!(pre_dispatch(1, &call));
+		assert_eq!(normal(), 2 * <() as WeightInfo>::note_call());
+
+		// Rejected calls are not counted.
+		assert!(pre_dispatch(1, &call).is_err());
+		assert_eq!(normal(), 2 * <() as WeightInfo>::note_call());
+	});
+}
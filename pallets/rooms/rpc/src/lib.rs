@@ -0,0 +1,141 @@
+//! RPC interface for the rooms pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+use pallet_rooms_runtime_api::{Room, RoomId};
+pub use pallet_rooms_runtime_api::ListenApi as ListenRuntimeApi;
+
+/// Maximum number of members returned by a single `listen_members` call.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+#[rpc]
+pub trait ListenApi<BlockHash, AccountId, Balance> {
+	/// The open room `room`, if any.
+	#[rpc(name = "listen_room")]
+	fn room(&self, room: RoomId, at: Option<BlockHash>) -> Result<Option<Room<AccountId, Balance>>>;
+
+	/// Up to `limit` members of `room`, at most `MAX_PAGE_SIZE`, skipping the first `offset`.
+	#[rpc(name = "listen_members")]
+	fn members(
+		&self,
+		room: RoomId,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<AccountId>>;
+
+	/// The open rooms owned by `owner`.
+	#[rpc(name = "listen_roomsByOwner")]
+	fn rooms_by_owner(&self, owner: AccountId, at: Option<BlockHash>) -> Result<Vec<RoomId>>;
+
+	/// The open rooms `who` is a member of, including the rooms it owns.
+	#[rpc(name = "listen_joinedRooms")]
+	fn joined_rooms(&self, who: AccountId, at: Option<BlockHash>) -> Result<Vec<RoomId>>;
+}
+
+/// A struct that implements the [`ListenApi`].
+pub struct Listen<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Listen<C, P> {
+	/// Create new `Listen` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// More than `MAX_PAGE_SIZE` members were requested.
+	PageTooLarge,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::PageTooLarge => 2,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query rooms.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block> Listen<C, Block> where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn at(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		))
+	}
+}
+
+impl<C, Block, AccountId, Balance> ListenApi<<Block as BlockT>::Hash, AccountId, Balance>
+	for Listen<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: ListenRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	fn room(
+		&self,
+		room: RoomId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Room<AccountId, Balance>>> {
+		self.client.runtime_api().room(&self.at(at), room).map_err(runtime_error)
+	}
+
+	fn members(
+		&self,
+		room: RoomId,
+		offset: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<AccountId>> {
+		if limit > MAX_PAGE_SIZE {
+			return Err(RpcError {
+				code: ErrorCode::ServerError(Error::PageTooLarge.into()),
+				message: format!("At most {} members can be listed at once.", MAX_PAGE_SIZE),
+				data: None,
+			})
+		}
+		self.client.runtime_api().members(&self.at(at), room, offset, limit).map_err(runtime_error)
+	}
+
+	fn rooms_by_owner(
+		&self,
+		owner: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RoomId>> {
+		self.client.runtime_api().rooms_by_owner(&self.at(at), owner).map_err(runtime_error)
+	}
+
+	fn joined_rooms(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RoomId>> {
+		self.client.runtime_api().joined_rooms(&self.at(at), who).map_err(runtime_error)
+	}
+}
@@ -0,0 +1,28 @@
+//! Runtime API definition for the rooms pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_rooms::{Room, RoomId};
+
+sp_api::decl_runtime_apis! {
+	/// Lookup of rooms, their members and the rooms of an account.
+	pub trait ListenApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The open room `room`, if any.
+		fn room(room: RoomId) -> Option<Room<AccountId, Balance>>;
+
+		/// Up to `limit` members of `room`, skipping the first `offset`.
+		fn members(room: RoomId, offset: u32, limit: u32) -> Vec<AccountId>;
+
+		/// The open rooms owned by `owner`.
+		fn rooms_by_owner(owner: AccountId) -> Vec<RoomId>;
+
+		/// The open rooms `who` is a member of, including the rooms it owns.
+		fn joined_rooms(who: AccountId) -> Vec<RoomId>;
+	}
+}
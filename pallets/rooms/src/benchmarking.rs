@@ -0,0 +1,70 @@
+//! Benchmarking setup for pallet-rooms
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Rooms;
+
+/// A room opened by a funded whitelisted caller.
+fn open_room<T: Config>() -> (T::AccountId, RoomId) {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	let room = NextRoomId::get();
+	Rooms::<T>::open(RawOrigin::Signed(caller.clone()).into()).expect("The caller can pay");
+	(caller, room)
+}
+
+benchmarks! {
+	open {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let room = NextRoomId::get();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Rooms::<T>::is_owner(&caller, room));
+	}
+
+	join {
+		let (_, room) = open_room::<T>();
+		let member: T::AccountId = account("member", 0, 0);
+	}: _(RawOrigin::Signed(member.clone()), room)
+	verify {
+		assert!(Rooms::<T>::is_member(&member, room));
+	}
+
+	leave {
+		let (_, room) = open_room::<T>();
+		let member: T::AccountId = account("member", 0, 0);
+		Rooms::<T>::join(RawOrigin::Signed(member.clone()).into(), room)?;
+	}: _(RawOrigin::Signed(member.clone()), room)
+	verify {
+		assert!(!Rooms::<T>::is_member(&member, room));
+	}
+
+	close {
+		let (caller, room) = open_room::<T>();
+	}: _(RawOrigin::Signed(caller), room)
+	verify {
+		assert_eq!(Rooms::<T>::room(room), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_open::<Test>());
+			assert_ok!(test_benchmark_join::<Test>());
+			assert_ok!(test_benchmark_leave::<Test>());
+			assert_ok!(test_benchmark_close::<Test>());
+		});
+	}
+}
@@ -0,0 +1,292 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Rooms with an owner and members.
+//!
+//! Any account can open a room by reserving a deposit; it becomes the room's owner and first
+//! member, and gets a fresh room id, so rooms cannot be squatted. Other accounts join and leave
+//! freely, and the owner can remove members. The owner closes the room once everyone else has
+//! left, which returns the deposit. Reaped accounts leave every room they joined.
+//!
+//! Other pallets learn who owns a room and who is in it through `Module::is_owner` and
+//! `Module::is_member`. The rooms of an account, owned or joined, are indexed by account so that
+//! clients can list them through the `ListenApi` runtime API without scanning every room.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+	traits::{Currency, Get, OnKilledAccount, ReservableCurrency},
+};
+use frame_system::ensure_signed;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of a room.
+pub type RoomId = u64;
+
+/// An open room.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Room<AccountId, Balance> {
+	/// The account that opened the room and holds the deposit.
+	pub owner: AccountId,
+	/// The deposit reserved from the owner.
+	pub deposit: Balance,
+	/// Number of members, including the owner.
+	pub members: u32,
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The currency in which room deposits are reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// The deposit reserved from the owner while a room is open.
+	type RoomDeposit: Get<BalanceOf<Self>>;
+	/// Maximum number of members of a room, including its owner.
+	type MaxMembers: Get<u32>;
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Rooms {
+		/// The id of the next room to be opened.
+		NextRoomId get(fn next_room_id): RoomId;
+
+		/// Open rooms by id.
+		Rooms get(fn room):
+			map hasher(twox_64_concat) RoomId => Option<Room<T::AccountId, BalanceOf<T>>>;
+
+		/// The members of every room.
+		Members:
+			double_map hasher(twox_64_concat) RoomId, hasher(blake2_128_concat) T::AccountId
+			=> ();
+
+		/// The open rooms of every owner.
+		RoomsByOwner:
+			double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) RoomId
+			=> ();
+
+		/// The rooms every account is a member of, including the rooms it owns.
+		JoinedRooms:
+			double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) RoomId
+			=> ();
+
+		/// Number of open rooms.
+		pub RoomCount get(fn room_count): u32;
+
+		/// Number of members of all open rooms; an account in two rooms counts twice.
+		pub MemberCount get(fn member_count): u32;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
+		/// A room was opened. [room, owner]
+		Opened(RoomId, AccountId),
+		/// An account joined a room. [room, who]
+		Joined(RoomId, AccountId),
+		/// An account left or was removed from a room. [room, who]
+		Left(RoomId, AccountId),
+		/// A room was closed and its deposit returned. [room, owner]
+		Closed(RoomId, AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// No open room has this id.
+		UnknownRoom,
+		/// Only the owner of the room can do this.
+		NotOwner,
+		/// The account is already a member of the room.
+		AlreadyMember,
+		/// The account is not a member of the room.
+		NotMember,
+		/// The room already has `MaxMembers` members.
+		RoomFull,
+		/// The owner cannot leave their room, only close it.
+		OwnerCannotLeave,
+		/// The room still has members other than its owner.
+		RoomNotEmpty,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		const RoomDeposit: BalanceOf<T> = T::RoomDeposit::get();
+		const MaxMembers: u32 = T::MaxMembers::get();
+
+		fn deposit_event() = default;
+
+		/// Open a new room owned by the origin, reserving `RoomDeposit`.
+		#[weight = T::WeightInfo::open()]
+		pub fn open(origin) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			let deposit = T::RoomDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+
+			let room = NextRoomId::mutate(|next| {
+				let room = *next;
+				*next += 1;
+				room
+			});
+			Self::insert_room(room, Room { owner: who.clone(), deposit, members: 1 });
+
+			Self::deposit_event(RawEvent::Opened(room, who));
+			Ok(())
+		}
+
+		/// Join `room`.
+		#[weight = T::WeightInfo::join()]
+		pub fn join(origin, room: RoomId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut info = Rooms::<T>::get(room).ok_or(Error::<T>::UnknownRoom)?;
+			ensure!(!Members::<T>::contains_key(room, &who), Error::<T>::AlreadyMember);
+			ensure!(info.members < T::MaxMembers::get(), Error::<T>::RoomFull);
+
+			info.members += 1;
+			Rooms::<T>::insert(room, info);
+			Members::<T>::insert(room, &who, ());
+			JoinedRooms::<T>::insert(&who, room, ());
+			MemberCount::mutate(|count| *count += 1);
+
+			Self::deposit_event(RawEvent::Joined(room, who));
+			Ok(())
+		}
+
+		/// Leave `room`.
+		#[weight = T::WeightInfo::leave()]
+		pub fn leave(origin, room: RoomId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::remove(room, who)
+		}
+
+		/// Remove `who` from a room owned by the origin.
+		#[weight = T::WeightInfo::leave()]
+		pub fn remove_member(origin, room: RoomId, who: T::AccountId) -> dispatch::DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(Self::is_owner(&owner, room), Error::<T>::NotOwner);
+			Self::remove(room, who)
+		}
+
+		/// Close a room owned by the origin once it is its only member, returning the deposit.
+		#[weight = T::WeightInfo::close()]
+		pub fn close(origin, room: RoomId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			let info = Rooms::<T>::get(room).ok_or(Error::<T>::UnknownRoom)?;
+			ensure!(info.owner == who, Error::<T>::NotOwner);
+			ensure!(info.members == 1, Error::<T>::RoomNotEmpty);
+
+			Rooms::<T>::remove(room);
+			Members::<T>::remove(room, &who);
+			RoomsByOwner::<T>::remove(&who, room);
+			JoinedRooms::<T>::remove(&who, room);
+			RoomCount::mutate(|count| *count = count.saturating_sub(1));
+			MemberCount::mutate(|count| *count = count.saturating_sub(1));
+			T::Currency::unreserve(&who, info.deposit);
+
+			Self::deposit_event(RawEvent::Closed(room, who));
+			Ok(())
+		}
+	}
+}
+
+/// Removes reaped accounts from the rooms they joined. Owners reserve a room's deposit, which keeps
+/// their accounts alive while the room is open.
+impl<T: Config> OnKilledAccount<T::AccountId> for Module<T> {
+	fn on_killed_account(who: &T::AccountId) {
+		for room in Self::joined_rooms(who) {
+			let _ = Self::remove(room, who.clone());
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Whether `who` owns the open room `room`.
+	pub fn is_owner(who: &T::AccountId, room: RoomId) -> bool {
+		Rooms::<T>::get(room).map_or(false, |info| &info.owner == who)
+	}
+
+	/// Whether `who` is a member of the open room `room`. Owners are members of their rooms.
+	pub fn is_member(who: &T::AccountId, room: RoomId) -> bool {
+		Members::<T>::contains_key(room, who)
+	}
+
+	/// Up to `limit` members of `room`, skipping the first `offset`. Members are listed in a fixed
+	/// order as long as the room's membership does not change.
+	pub fn members(room: RoomId, offset: u32, limit: u32) -> Vec<T::AccountId> {
+		Members::<T>::iter_prefix(room)
+			.skip(offset as usize)
+			.take(limit as usize)
+			.map(|(who, ())| who)
+			.collect()
+	}
+
+	/// The open rooms owned by `who`.
+	pub fn rooms_by_owner(who: &T::AccountId) -> Vec<RoomId> {
+		RoomsByOwner::<T>::iter_prefix(who).map(|(room, ())| room).collect()
+	}
+
+	/// The open rooms `who` is a member of, including the rooms it owns.
+	pub fn joined_rooms(who: &T::AccountId) -> Vec<RoomId> {
+		JoinedRooms::<T>::iter_prefix(who).map(|(room, ())| room).collect()
+	}
+
+	/// Store a newly opened room with its owner as only member.
+	fn insert_room(room: RoomId, info: Room<T::AccountId, BalanceOf<T>>) {
+		Members::<T>::insert(room, &info.owner, ());
+		RoomsByOwner::<T>::insert(&info.owner, room, ());
+		JoinedRooms::<T>::insert(&info.owner, room, ());
+		Rooms::<T>::insert(room, info);
+		RoomCount::mutate(|count| *count += 1);
+		MemberCount::mutate(|count| *count += 1);
+	}
+
+	/// Remove `who` from `room`; owners can only be removed by closing the room.
+	fn remove(room: RoomId, who: T::AccountId) -> dispatch::DispatchResult {
+		let mut info = Rooms::<T>::get(room).ok_or(Error::<T>::UnknownRoom)?;
+		ensure!(Members::<T>::contains_key(room, &who), Error::<T>::NotMember);
+		ensure!(info.owner != who, Error::<T>::OwnerCannotLeave);
+
+		info.members -= 1;
+		Rooms::<T>::insert(room, info);
+		Members::<T>::remove(room, &who);
+		JoinedRooms::<T>::remove(&who, room);
+		MemberCount::mutate(|count| *count = count.saturating_sub(1));
+
+		Self::deposit_event(RawEvent::Left(room, who));
+		Ok(())
+	}
+
+	/// Open `room` for `owner` without a deposit, so that benchmarks of other pallets can use a
+	/// room with a known id.
+	#[cfg(feature = "runtime-benchmarks")]
+	pub fn open_for_benchmarks(owner: &T::AccountId, room: RoomId) {
+		if Rooms::<T>::contains_key(room) {
+			return
+		}
+		let info = Room { owner: owner.clone(), deposit: Default::default(), members: 1 };
+		Self::insert_room(room, info);
+	}
+}
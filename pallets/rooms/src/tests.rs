@@ -0,0 +1,140 @@
+use crate::{Error, RoomId, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::OnKilledAccount};
+
+#[test]
+fn opening_a_room_reserves_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::open(Origin::signed(2)));
+
+		let room = Rooms::room(0).unwrap();
+		assert_eq!(room.owner, 1);
+		assert_eq!(room.deposit, 10);
+		assert_eq!(room.members, 1);
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert!(Rooms::is_owner(&2, 1));
+		assert!(Rooms::is_member(&1, 0));
+		assert!(!Rooms::is_member(&1, 1));
+		assert_eq!(Rooms::room_count(), 2);
+
+		assert!(Rooms::open(Origin::signed(4)).is_err());
+	});
+}
+
+#[test]
+fn accounts_join_and_leave() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Rooms::join(Origin::signed(2), 0), Error::<Test>::UnknownRoom);
+		assert_ok!(Rooms::open(Origin::signed(1)));
+
+		assert_ok!(Rooms::join(Origin::signed(2), 0));
+		assert_noop!(Rooms::join(Origin::signed(2), 0), Error::<Test>::AlreadyMember);
+		assert_ok!(Rooms::join(Origin::signed(3), 0));
+		assert_noop!(Rooms::join(Origin::signed(4), 0), Error::<Test>::RoomFull);
+		assert_eq!(Rooms::room(0).unwrap().members, 3);
+		assert_eq!(Rooms::member_count(), 3);
+
+		assert_ok!(Rooms::leave(Origin::signed(2), 0));
+		assert!(!Rooms::is_member(&2, 0));
+		assert_noop!(Rooms::leave(Origin::signed(2), 0), Error::<Test>::NotMember);
+		assert_noop!(Rooms::leave(Origin::signed(1), 0), Error::<Test>::OwnerCannotLeave);
+		assert_eq!(Rooms::member_count(), 2);
+	});
+}
+
+#[test]
+fn only_the_owner_removes_members() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::join(Origin::signed(2), 0));
+		assert_ok!(Rooms::join(Origin::signed(3), 0));
+
+		assert_noop!(Rooms::remove_member(Origin::signed(2), 0, 3), Error::<Test>::NotOwner);
+		assert_ok!(Rooms::remove_member(Origin::signed(1), 0, 3));
+		assert!(!Rooms::is_member(&3, 0));
+		assert_noop!(
+			Rooms::remove_member(Origin::signed(1), 0, 1),
+			Error::<Test>::OwnerCannotLeave
+		);
+	});
+}
+
+#[test]
+fn closing_an_empty_room_returns_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::join(Origin::signed(2), 0));
+
+		assert_noop!(Rooms::close(Origin::signed(2), 0), Error::<Test>::NotOwner);
+		assert_noop!(Rooms::close(Origin::signed(1), 0), Error::<Test>::RoomNotEmpty);
+		assert_ok!(Rooms::leave(Origin::signed(2), 0));
+		assert_ok!(Rooms::close(Origin::signed(1), 0));
+
+		assert_eq!(Rooms::room(0), None);
+		assert!(!Rooms::is_member(&1, 0));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!((Rooms::room_count(), Rooms::member_count()), (0, 0));
+		// Room ids are never reused.
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert!(Rooms::is_owner(&1, 1));
+	});
+}
+
+#[test]
+fn rooms_are_indexed_by_owner_and_member() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::join(Origin::signed(2), 0));
+		assert_ok!(Rooms::join(Origin::signed(2), 1));
+
+		let sorted = |mut rooms: Vec<RoomId>| { rooms.sort(); rooms };
+		assert_eq!(sorted(Rooms::rooms_by_owner(&1)), vec![0, 1]);
+		assert_eq!(sorted(Rooms::joined_rooms(&1)), vec![0, 1]);
+		assert!(Rooms::rooms_by_owner(&2).is_empty());
+		assert_eq!(sorted(Rooms::joined_rooms(&2)), vec![0, 1]);
+
+		assert_ok!(Rooms::leave(Origin::signed(2), 0));
+		assert_eq!(Rooms::joined_rooms(&2), vec![1]);
+		assert_ok!(Rooms::close(Origin::signed(1), 0));
+		assert_eq!(Rooms::rooms_by_owner(&1), vec![1]);
+		assert_eq!(Rooms::joined_rooms(&1), vec![1]);
+	});
+}
+
+#[test]
+fn reaped_accounts_leave_the_rooms_they_joined() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::join(Origin::signed(2), 0));
+		assert_ok!(Rooms::join(Origin::signed(2), 1));
+
+		<Rooms as OnKilledAccount<u64>>::on_killed_account(&2);
+		assert!(Rooms::joined_rooms(&2).is_empty());
+		assert_eq!(Rooms::room(0).unwrap().members, 1);
+		assert_eq!(Rooms::member_count(), 2);
+
+		// Owners only leave by closing their rooms.
+		<Rooms as OnKilledAccount<u64>>::on_killed_account(&1);
+		assert_eq!(Rooms::room_count(), 2);
+		assert!(Rooms::is_member(&1, 0));
+	});
+}
+
+#[test]
+fn members_are_paged() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rooms::open(Origin::signed(1)));
+		assert_ok!(Rooms::join(Origin::signed(2), 0));
+		assert_ok!(Rooms::join(Origin::signed(3), 0));
+
+		let all = Rooms::members(0, 0, 10);
+		assert_eq!(all.len(), 3);
+		let mut paged = Rooms::members(0, 0, 2);
+		paged.extend(Rooms::members(0, 2, 2));
+		assert_eq!(paged, all);
+		assert!(Rooms::members(0, 3, 2).is_empty());
+		assert!(Rooms::members(1, 0, 10).is_empty());
+	});
+}
@@ -0,0 +1,40 @@
+//! Runtime API definition for the sponsorship pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+
+/// `RuntimeDispatchInfo` together with the account the fee will be charged to.
+#[derive(Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "std", serde(bound(
+	serialize = "AccountId: Serialize, Balance: std::fmt::Display",
+	deserialize = "AccountId: Deserialize<'de>, Balance: std::str::FromStr",
+)))]
+pub struct SponsoredDispatchInfo<AccountId, Balance> {
+	/// Weight, class and fee of the transaction.
+	#[cfg_attr(feature = "std", serde(flatten))]
+	pub info: RuntimeDispatchInfo<Balance>,
+	/// The signer, or the budget account of the room sponsoring the transaction.
+	pub payer: Option<AccountId>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Fee information that accounts for room sponsorship.
+	pub trait SponsorshipApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Like `TransactionPaymentApi::query_info`, but also reports who pays the fee, and a zero
+		/// fee for calls the runtime lets through for free.
+		///
+		/// `TransactionPaymentApi::query_info` itself cannot report the payer: its
+		/// `RuntimeDispatchInfo` is defined upstream and clients decode it as is.
+		fn query_info(uxt: Block::Extrinsic, len: u32) -> SponsoredDispatchInfo<AccountId, Balance>;
+	}
+}
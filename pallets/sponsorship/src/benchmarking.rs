@@ -0,0 +1,64 @@
+//! Benchmarking setup for pallet-sponsorship
+
+use super::*;
+
+use codec::Decode;
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Sponsorship;
+
+fn room_owner<T: Config>() -> (T::AccountId, T::RoomId) {
+	let caller: T::AccountId = whitelisted_caller();
+	let room: T::RoomId = Decode::decode(&mut &[0u8; 32][..]).unwrap();
+	T::RoomOwnership::set_owner(&caller, &room);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	(caller, room)
+}
+
+benchmarks! {
+	fund {
+		let (caller, room) = room_owner::<T>();
+		let amount = T::Currency::minimum_balance() * 100u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), room, amount)
+	verify {
+		assert_eq!(Sponsors::<T>::get(room), Some(caller));
+		assert_eq!(Sponsorship::<T>::budget(room), amount);
+	}
+
+	withdraw {
+		let (caller, room) = room_owner::<T>();
+		let amount = T::Currency::minimum_balance() * 100u32.into();
+		Sponsorship::<T>::fund(RawOrigin::Signed(caller.clone()).into(), room, amount)?;
+		let half = T::Currency::minimum_balance() * 50u32.into();
+	}: _(RawOrigin::Signed(caller), room, half)
+	verify {
+		assert_eq!(Sponsorship::<T>::budget(room), half);
+	}
+
+	stop {
+		let (caller, room) = room_owner::<T>();
+		let amount = T::Currency::minimum_balance() * 100u32.into();
+		Sponsorship::<T>::fund(RawOrigin::Signed(caller.clone()).into(), room, amount)?;
+	}: _(RawOrigin::Signed(caller), room)
+	verify {
+		assert_eq!(Sponsors::<T>::get(room), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_fund::<Test>());
+			assert_ok!(test_benchmark_withdraw::<Test>());
+			assert_ok!(test_benchmark_stop::<Test>());
+		});
+	}
+}
@@ -0,0 +1,307 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Room-sponsored transaction fees.
+//!
+//! A room owner funds a fee budget for the room. While the budget lasts, the fees of calls that
+//! members make inside the room are taken from it instead of from the member who signed them;
+//! once it is exhausted, members pay for themselves again. Calls of non-members are not
+//! sponsored, except for those `CallRoom::joins_room` reports as joining the room.
+//!
+//! A sponsored call is paid for when it is included, whether or not its dispatch succeeds, so a
+//! member could spend the budget on calls that fail. Runtimes should rate limit every call
+//! `CallRoom` maps to a room per signer, e.g. with `pallet-rate-limit`, and owners can remove
+//! members who abuse the budget from the room.
+//!
+//! Budgets are held by a per-room account derived from `ModuleId`. Fees are redirected by
+//! `SponsoredAdapter`, which wraps the runtime's `OnChargeTransaction` implementation and is
+//! therefore used by the fee-charging signed extension both when a transaction is validated for
+//! the pool and when it is dispatched. `payer` tells clients who a transaction will be charged to.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+	traits::{Currency, ExistenceRequirement, Get},
+	Parameter,
+};
+use frame_system::ensure_signed;
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::{
+	traits::{AccountIdConversion, DispatchInfoOf, Member, PostDispatchInfoOf, Saturating, Zero},
+	transaction_validity::TransactionValidityError,
+	ModuleId,
+};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Maps calls to the room they are made in.
+///
+/// Every call mapped to a room should be rate limited per signer, see the module docs.
+pub trait CallRoom<Call, RoomId> {
+	/// The room `call` is made in, if it is scoped to a room.
+	fn room_of(call: &Call) -> Option<RoomId>;
+
+	/// Whether `call` makes its signer a member of the room it is made in, so that the room
+	/// sponsors it for non-members too.
+	fn joins_room(_call: &Call) -> bool {
+		false
+	}
+}
+
+/// Decides who may sponsor the transactions of a room.
+pub trait RoomOwnership<AccountId, RoomId> {
+	/// Whether `who` owns `room`.
+	fn is_owner(who: &AccountId, room: &RoomId) -> bool;
+
+	/// Make `who` an owner of `room`.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_owner(who: &AccountId, room: &RoomId);
+}
+
+/// Decides whose transactions a room sponsors.
+pub trait RoomMembership<AccountId, RoomId> {
+	/// Whether `who` is a member of `room`.
+	fn is_member(who: &AccountId, room: &RoomId) -> bool;
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The currency budgets are held in.
+	type Currency: Currency<Self::AccountId>;
+	/// Identifier of a room.
+	type RoomId: Parameter + Member + Copy;
+	/// The room a call is made in.
+	type CallRoom: CallRoom<<Self as frame_system::Config>::Call, Self::RoomId>;
+	/// Who may fund the budget of a room.
+	type RoomOwnership: RoomOwnership<Self::AccountId, Self::RoomId>;
+	/// Whose calls the budget of a room pays for.
+	type RoomMembership: RoomMembership<Self::AccountId, Self::RoomId>;
+	/// The id from which the budget accounts of rooms are derived.
+	type ModuleId: Get<ModuleId>;
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Sponsorship {
+		/// The account sponsoring every room.
+		Sponsors get(fn sponsor): map hasher(blake2_128_concat) T::RoomId => Option<T::AccountId>;
+
+		/// Number of sponsored rooms.
+		pub SponsoredRooms get(fn sponsored_rooms): u32;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		RoomId = <T as Config>::RoomId,
+		Balance = BalanceOf<T>,
+	{
+		/// A sponsor added funds to the budget of a room. [room, sponsor, amount]
+		Funded(RoomId, AccountId, Balance),
+		/// A sponsor took funds out of the budget of a room. [room, sponsor, amount]
+		Withdrawn(RoomId, AccountId, Balance),
+		/// A sponsor stopped sponsoring a room and took back its budget. [room, sponsor, amount]
+		Stopped(RoomId, AccountId, Balance),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// Only the owner of a room can sponsor it.
+		NotRoomOwner,
+		/// The room is sponsored by another account.
+		SponsoredByOther,
+		/// The room is not sponsored.
+		NotSponsored,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		fn deposit_event() = default;
+
+		/// Add `amount` to the fee budget of `room`, becoming its sponsor.
+		#[weight = T::WeightInfo::fund()]
+		pub fn fund(
+			origin,
+			room: T::RoomId,
+			#[compact] amount: BalanceOf<T>,
+		) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(T::RoomOwnership::is_owner(&who, &room), Error::<T>::NotRoomOwner);
+			let sponsor = Sponsors::<T>::get(room);
+			if let Some(sponsor) = &sponsor {
+				ensure!(sponsor == &who, Error::<T>::SponsoredByOther);
+			}
+
+			T::Currency::transfer(
+				&who,
+				&Self::budget_account(room),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			if sponsor.is_none() {
+				Sponsors::<T>::insert(room, &who);
+				SponsoredRooms::mutate(|count| *count += 1);
+			}
+
+			Self::deposit_event(RawEvent::Funded(room, who, amount));
+			Ok(())
+		}
+
+		/// Take `amount` out of the fee budget of a room sponsored by the origin.
+		#[weight = T::WeightInfo::withdraw()]
+		pub fn withdraw(
+			origin,
+			room: T::RoomId,
+			#[compact] amount: BalanceOf<T>,
+		) -> dispatch::DispatchResult {
+			let who = Self::ensure_sponsor(origin, room)?;
+
+			T::Currency::transfer(
+				&Self::budget_account(room),
+				&who,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			Self::deposit_event(RawEvent::Withdrawn(room, who, amount));
+			Ok(())
+		}
+
+		/// Stop sponsoring a room and take back what is left of its budget.
+		#[weight = T::WeightInfo::stop()]
+		pub fn stop(origin, room: T::RoomId) -> dispatch::DispatchResult {
+			let who = Self::ensure_sponsor(origin, room)?;
+
+			let budget = Self::budget_account(room);
+			let amount = T::Currency::free_balance(&budget);
+			T::Currency::transfer(&budget, &who, amount, ExistenceRequirement::AllowDeath)?;
+			Sponsors::<T>::remove(room);
+			SponsoredRooms::mutate(|count| *count = count.saturating_sub(1));
+
+			Self::deposit_event(RawEvent::Stopped(room, who, amount));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The account holding the fee budget of `room`.
+	pub fn budget_account(room: T::RoomId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(room)
+	}
+
+	/// What is left of the fee budget of `room`.
+	pub fn budget(room: T::RoomId) -> BalanceOf<T> {
+		T::Currency::free_balance(&Self::budget_account(room))
+	}
+
+	/// The budget account that sponsors `call` signed by `who`, if any.
+	pub fn sponsor_of(
+		who: &T::AccountId,
+		call: &<T as frame_system::Config>::Call,
+	) -> Option<T::AccountId> {
+		T::CallRoom::room_of(call)
+			.filter(|room| Sponsors::<T>::contains_key(room))
+			.filter(|room| T::CallRoom::joins_room(call) || T::RoomMembership::is_member(who, room))
+			.map(Self::budget_account)
+	}
+
+	/// The account that pays `fee` and `tip` for `call` signed by `who`.
+	///
+	/// Mirrors `SponsoredAdapter`: the room budget pays untipped transactions if it can cover the
+	/// fee and stay alive.
+	pub fn payer(
+		who: &T::AccountId,
+		call: &<T as frame_system::Config>::Call,
+		fee: BalanceOf<T>,
+		tip: BalanceOf<T>,
+	) -> T::AccountId {
+		if !tip.is_zero() {
+			return who.clone()
+		}
+		Self::sponsor_of(who, call)
+			.filter(|budget| {
+				T::Currency::free_balance(budget).saturating_sub(fee)
+					>= T::Currency::minimum_balance()
+			})
+			.unwrap_or_else(|| who.clone())
+	}
+
+	fn ensure_sponsor(
+		origin: T::Origin,
+		room: T::RoomId,
+	) -> Result<T::AccountId, dispatch::DispatchError> {
+		let who = ensure_signed(origin)?;
+		let sponsor = Sponsors::<T>::get(room).ok_or(Error::<T>::NotSponsored)?;
+		ensure!(sponsor == who, Error::<T>::SponsoredByOther);
+		Ok(who)
+	}
+}
+
+/// `OnChargeTransaction` that takes the fees of sponsored room calls from the room's budget and
+/// delegates everything else, including the actual withdrawal, to `OCT`.
+///
+/// Transactions with a tip are always charged to their signer: a sponsor pays for inclusion, not
+/// for priority.
+pub struct SponsoredAdapter<T, OCT>(PhantomData<(T, OCT)>);
+
+impl<T, OCT> OnChargeTransaction<T> for SponsoredAdapter<T, OCT> where
+	T: Config + pallet_transaction_payment::Config,
+	OCT: OnChargeTransaction<T>,
+{
+	type Balance = OCT::Balance;
+	/// The budget account if the fee was sponsored, and what `OCT` withdrew.
+	type LiquidityInfo = (Option<T::AccountId>, OCT::LiquidityInfo);
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &<T as frame_system::Config>::Call,
+		info: &DispatchInfoOf<<T as frame_system::Config>::Call>,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		if tip.is_zero() {
+			if let Some(budget) = Module::<T>::sponsor_of(who, call) {
+				// An exhausted budget falls back to the signer.
+				if let Ok(liquidity) = OCT::withdraw_fee(&budget, call, info, fee, tip) {
+					return Ok((Some(budget), liquidity))
+				}
+			}
+		}
+		OCT::withdraw_fee(who, call, info, fee, tip).map(|liquidity| (None, liquidity))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		info: &DispatchInfoOf<<T as frame_system::Config>::Call>,
+		post_info: &PostDispatchInfoOf<<T as frame_system::Config>::Call>,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		(payer, already_withdrawn): Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		let payer = payer.unwrap_or_else(|| who.clone());
+		OCT::correct_and_deposit_fee(&payer, info, post_info, corrected_fee, tip, already_withdrawn)
+	}
+}
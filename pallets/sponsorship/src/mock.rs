@@ -0,0 +1,169 @@
+use crate as pallet_sponsorship;
+use std::cell::RefCell;
+use sp_core::H256;
+use frame_support::{parameter_types, weights::IdentityFee};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, ModuleId,
+};
+use frame_system as system;
+use pallet_transaction_payment::CurrencyAdapter;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		Sponsorship: pallet_sponsorship::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	// Wide enough for the budget accounts of different rooms to differ.
+	type AccountId = u128;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const TransactionByteFee: u64 = 1;
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type OnChargeTransaction = pallet_sponsorship::SponsoredAdapter<
+		Test,
+		CurrencyAdapter<Balances, ()>,
+	>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+/// `System::remark` is made in the room given by its first byte, and joins it if its second byte
+/// is 1.
+pub struct RemarkRoom;
+impl pallet_sponsorship::CallRoom<Call, u64> for RemarkRoom {
+	fn room_of(call: &Call) -> Option<u64> {
+		match call {
+			Call::System(frame_system::Call::remark(data)) => data.first().map(|room| *room as u64),
+			_ => None,
+		}
+	}
+
+	fn joins_room(call: &Call) -> bool {
+		matches!(call, Call::System(frame_system::Call::remark(data)) if data.get(1) == Some(&1))
+	}
+}
+
+thread_local! {
+	static OWNERS: RefCell<Vec<(u128, u64)>> = RefCell::new(vec![]);
+	static MEMBERS: RefCell<Vec<(u128, u64)>> = RefCell::new(vec![]);
+}
+
+pub struct Owners;
+impl pallet_sponsorship::RoomOwnership<u128, u64> for Owners {
+	fn is_owner(who: &u128, room: &u64) -> bool {
+		OWNERS.with(|owners| owners.borrow().contains(&(*who, *room)))
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_owner(who: &u128, room: &u64) {
+		OWNERS.with(|owners| owners.borrow_mut().push((*who, *room)));
+	}
+}
+
+pub struct Members;
+impl pallet_sponsorship::RoomMembership<u128, u64> for Members {
+	fn is_member(who: &u128, room: &u64) -> bool {
+		MEMBERS.with(|members| members.borrow().contains(&(*who, *room)))
+	}
+}
+
+parameter_types! {
+	pub const SponsorshipModuleId: ModuleId = ModuleId(*b"lstn/spn");
+}
+
+impl pallet_sponsorship::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type RoomId = u64;
+	type CallRoom = RemarkRoom;
+	type RoomOwnership = Owners;
+	type RoomMembership = Members;
+	type ModuleId = SponsorshipModuleId;
+	type WeightInfo = ();
+}
+
+/// Owns room 1.
+pub const HOST: u128 = 1;
+/// Also owns room 1.
+pub const CO_HOST: u128 = 2;
+/// A member of room 1 without any funds.
+pub const MEMBER: u128 = 3;
+/// A member of room 1 that can pay for its transactions.
+pub const RICH: u128 = 4;
+/// Not a member of any room, and without funds.
+pub const STRANGER: u128 = 5;
+
+/// The fee of the transactions in these tests.
+pub const FEE: u64 = 1_000_000_000;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	OWNERS.with(|owners| *owners.borrow_mut() = vec![(HOST, 1), (CO_HOST, 1)]);
+	MEMBERS.with(|members| {
+		*members.borrow_mut() = vec![(HOST, 1), (CO_HOST, 1), (MEMBER, 1), (RICH, 1)]
+	});
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(HOST, 100 * FEE), (CO_HOST, 100 * FEE), (RICH, 100 * FEE)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,156 @@
+use crate::{Error, mock::*};
+use frame_support::{
+	assert_ok, assert_noop,
+	weights::{DispatchInfo, GetDispatchInfo, PostDispatchInfo},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::traits::SignedExtension;
+
+fn remark_in(room: u8) -> Call {
+	Call::System(frame_system::Call::remark(vec![room]))
+}
+
+/// Charge and settle the fee of `call` signed by `who` the way the signed extension does.
+fn charge(who: u128, call: &Call, tip: u64) -> Result<(), ()> {
+	let info: DispatchInfo = call.get_dispatch_info();
+	let pre = ChargeTransactionPayment::<Test>::from(tip)
+		.pre_dispatch(&who, call, &info, 10)
+		.map_err(|_| ())?;
+	ChargeTransactionPayment::<Test>::post_dispatch(
+		pre, &info, &PostDispatchInfo::default(), 10, &Ok(()),
+	).map_err(|_| ())
+}
+
+fn fee_of(call: &Call, tip: u64) -> u64 {
+	TransactionPayment::compute_fee(10, &call.get_dispatch_info(), tip)
+}
+
+#[test]
+fn only_room_owners_can_fund() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Sponsorship::fund(Origin::signed(RICH), 1, FEE), Error::<Test>::NotRoomOwner);
+
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+		assert_eq!(Sponsorship::sponsor(1), Some(HOST));
+		assert_eq!(Sponsorship::sponsored_rooms(), 1);
+		assert_eq!(Sponsorship::budget(1), 10 * FEE);
+		assert_eq!(Balances::free_balance(HOST), 90 * FEE);
+
+		// A co-host cannot take over an existing sponsorship.
+		assert_noop!(
+			Sponsorship::fund(Origin::signed(CO_HOST), 1, FEE),
+			Error::<Test>::SponsoredByOther
+		);
+	});
+}
+
+#[test]
+fn sponsored_calls_are_paid_by_the_budget() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+		let call = remark_in(1);
+		let fee = fee_of(&call, 0);
+
+		assert_eq!(Sponsorship::payer(&MEMBER, &call, fee, 0), Sponsorship::budget_account(1));
+		assert_eq!(charge(MEMBER, &call, 0), Ok(()));
+		assert_eq!(Sponsorship::budget(1), 10 * FEE - fee);
+		assert_eq!(Balances::free_balance(MEMBER), 0);
+	});
+}
+
+#[test]
+fn calls_outside_sponsored_rooms_are_paid_by_the_signer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+
+		let call = remark_in(2);
+		assert_eq!(Sponsorship::payer(&RICH, &call, fee_of(&call, 0), 0), RICH);
+		assert_eq!(charge(MEMBER, &call, 0), Err(()));
+		assert_eq!(charge(RICH, &call, 0), Ok(()));
+		assert_eq!(Balances::free_balance(RICH), 100 * FEE - fee_of(&call, 0));
+		assert_eq!(Sponsorship::budget(1), 10 * FEE);
+	});
+}
+
+#[test]
+fn calls_of_non_members_are_paid_by_the_signer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+		let call = remark_in(1);
+
+		assert_eq!(Sponsorship::sponsor_of(&STRANGER, &call), None);
+		assert_eq!(Sponsorship::payer(&STRANGER, &call, fee_of(&call, 0), 0), STRANGER);
+		assert_eq!(charge(STRANGER, &call, 0), Err(()));
+		assert_eq!(Sponsorship::budget(1), 10 * FEE);
+	});
+}
+
+#[test]
+fn joining_a_sponsored_room_is_sponsored() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+		let join = Call::System(frame_system::Call::remark(vec![1, 1]));
+
+		let budget = Sponsorship::budget_account(1);
+		assert_eq!(Sponsorship::sponsor_of(&STRANGER, &join), Some(budget.clone()));
+		assert_eq!(Sponsorship::payer(&STRANGER, &join, fee_of(&join, 0), 0), budget);
+		assert_ok!(charge(STRANGER, &join, 0));
+		assert_eq!(Sponsorship::budget(1), 10 * FEE - fee_of(&join, 0));
+	});
+}
+
+#[test]
+fn tips_are_paid_by_the_signer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+		let call = remark_in(1);
+
+		assert_eq!(Sponsorship::payer(&RICH, &call, fee_of(&call, 0), 5), RICH);
+		assert_eq!(charge(RICH, &call, 5), Ok(()));
+		assert_eq!(Balances::free_balance(RICH), 100 * FEE - fee_of(&call, 5));
+		assert_eq!(Sponsorship::budget(1), 10 * FEE);
+	});
+}
+
+#[test]
+fn exhausted_budget_falls_back_to_the_signer() {
+	new_test_ext().execute_with(|| {
+		let call = remark_in(1);
+		let fee = fee_of(&call, 0);
+		// Enough for one fee, but not for a second one.
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, fee + fee / 2));
+
+		assert_eq!(charge(RICH, &call, 0), Ok(()));
+		assert_eq!(Balances::free_balance(RICH), 100 * FEE);
+
+		assert_eq!(Sponsorship::payer(&RICH, &call, fee, 0), RICH);
+		assert_eq!(charge(RICH, &call, 0), Ok(()));
+		assert_eq!(Balances::free_balance(RICH), 100 * FEE - fee);
+		assert_eq!(charge(MEMBER, &call, 0), Err(()));
+	});
+}
+
+#[test]
+fn only_the_sponsor_can_withdraw_or_stop() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Sponsorship::withdraw(Origin::signed(HOST), 1, FEE), Error::<Test>::NotSponsored);
+		assert_ok!(Sponsorship::fund(Origin::signed(HOST), 1, 10 * FEE));
+
+		assert_noop!(
+			Sponsorship::withdraw(Origin::signed(CO_HOST), 1, FEE),
+			Error::<Test>::SponsoredByOther
+		);
+		assert_noop!(Sponsorship::stop(Origin::signed(CO_HOST), 1), Error::<Test>::SponsoredByOther);
+
+		assert_ok!(Sponsorship::withdraw(Origin::signed(HOST), 1, 4 * FEE));
+		assert_eq!(Sponsorship::budget(1), 6 * FEE);
+		assert_ok!(Sponsorship::stop(Origin::signed(HOST), 1));
+		assert_eq!(Sponsorship::sponsor(1), None);
+		assert_eq!(Sponsorship::sponsored_rooms(), 0);
+		assert_eq!(Sponsorship::budget(1), 0);
+		assert_eq!(Balances::free_balance(HOST), 100 * FEE);
+
+		// Once stopped, room calls are paid by their signers again.
+		assert_eq!(charge(MEMBER, &remark_in(1), 0), Err(()));
+	});
+}
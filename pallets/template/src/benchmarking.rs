@@ -0,0 +1,39 @@
+//! Benchmarking setup for pallet-template
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::StorageValue;
+
+benchmarks! {
+	do_something {
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Something::get(), Some(42));
+	}
+
+	cause_error {
+		Something::put(0);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(Something::get(), Some(1));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_do_something::<Test>());
+			assert_ok!(test_benchmark_cause_error::<Test>());
+		});
+	}
+}
@@ -0,0 +1,42 @@
+//! Benchmarking setup for pallet-welcome-bonus
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_support::traits::OnFinalize;
+use sp_runtime::traits::{Bounded, One};
+
+use crate::Module as WelcomeBonus;
+
+benchmarks! {
+	on_finalize {
+		let b in 0 .. T::MaxBonusesPerBlock::get();
+		frame_system::Module::<T>::set_block_number(One::one());
+		let pot = WelcomeBonus::<T>::pot_account();
+		T::Currency::make_free_balance_be(&pot, BalanceOf::<T>::max_value() / 2u32.into());
+		for i in 0 .. b {
+			let who: T::AccountId = account("newcomer", i, 0);
+			T::Currency::make_free_balance_be(&who, T::Currency::minimum_balance());
+		}
+		assert_eq!(Newcomers::<T>::decode_len().unwrap_or(0), b as usize);
+	}: {
+		WelcomeBonus::<T>::on_finalize(One::one());
+	}
+	verify {
+		assert!(Newcomers::<T>::get().is_empty());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext(1_000).execute_with(|| {
+			assert_ok!(test_benchmark_on_finalize::<Test>());
+		});
+	}
+}
@@ -0,0 +1,140 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A welcome bonus for new accounts.
+//!
+//! Every account created after genesis receives `Bonus` from a pot account derived from
+//! `ModuleId`, so that a new user can pay for a few transactions once their free quota runs out.
+//! The pot is funded by plain transfers; while it is empty, no bonus is paid.
+//!
+//! The runtime sets `frame_system::Config::OnNewAccount` to this pallet. Accounts are created in
+//! the middle of balance updates, so the hook only records them and the bonuses are paid when the
+//! block is finalized, to at most `MaxBonusesPerBlock` accounts per block. Accounts reaped before
+//! then get nothing. The weight of paying a bonus is registered when the account is recorded, so a
+//! block only accounts for the bonuses it actually pays.
+//!
+//! A bonus smaller than the fee of the transfer creating an account makes farming it with new
+//! accounts a losing game.
+
+use frame_support::{
+	decl_event, decl_module, decl_storage,
+	traits::{Currency, ExistenceRequirement, Get, OnNewAccount},
+	weights::{DispatchClass, Weight},
+};
+use sp_runtime::{
+	traits::{AccountIdConversion, Zero},
+	ModuleId,
+};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The currency the bonus is paid in.
+	type Currency: Currency<Self::AccountId>;
+	/// The bonus paid to every new account.
+	type Bonus: Get<BalanceOf<Self>>;
+	/// Maximum number of bonuses paid per block.
+	type MaxBonusesPerBlock: Get<u32>;
+	/// The id from which the pot account is derived.
+	type ModuleId: Get<ModuleId>;
+	/// Weight information for the hooks of this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as WelcomeBonus {
+		/// Accounts created in the current block, to be paid a bonus when it is finalized.
+		Newcomers get(fn newcomers): Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// A new account was paid its welcome bonus. [account, bonus]
+		BonusPaid(AccountId, Balance),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		const Bonus: BalanceOf<T> = T::Bonus::get();
+		const MaxBonusesPerBlock: u32 = T::MaxBonusesPerBlock::get();
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		fn deposit_event() = default;
+
+		fn on_initialize() -> Weight {
+			// The bonuses themselves are accounted for as the new accounts are recorded.
+			T::WeightInfo::on_finalize(0)
+		}
+
+		fn on_finalize() {
+			let pot = Self::pot_account();
+			let bonus = T::Bonus::get();
+			for who in Newcomers::<T>::take() {
+				// Paying an account that is gone would create it again.
+				if !frame_system::Module::<T>::account_exists(&who) {
+					continue
+				}
+				let paid = T::Currency::transfer(
+					&pot,
+					&who,
+					bonus,
+					ExistenceRequirement::KeepAlive,
+				);
+				if paid.is_ok() {
+					Self::deposit_event(RawEvent::BonusPaid(who, bonus));
+				}
+			}
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The account the bonuses are paid from.
+	pub fn pot_account() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	/// The weight of paying one bonus when the block is finalized.
+	fn bonus_weight() -> Weight {
+		T::WeightInfo::on_finalize(1).saturating_sub(T::WeightInfo::on_finalize(0))
+	}
+}
+
+/// Records new accounts, to pay their bonus at the end of the block.
+impl<T: Config> OnNewAccount<T::AccountId> for Module<T> {
+	fn on_new_account(who: &T::AccountId) {
+		// Genesis accounts are endowed already.
+		if frame_system::Module::<T>::block_number().is_zero() || *who == Self::pot_account() {
+			return
+		}
+		let newcomers = Newcomers::<T>::decode_len().unwrap_or(0);
+		if newcomers < T::MaxBonusesPerBlock::get() as usize {
+			Newcomers::<T>::append(who);
+			frame_system::Module::<T>::register_extra_weight_unchecked(
+				Self::bonus_weight(),
+				DispatchClass::Mandatory,
+			);
+		}
+	}
+}
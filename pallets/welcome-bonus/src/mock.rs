@@ -0,0 +1,101 @@
+use crate as pallet_welcome_bonus;
+use sp_core::H256;
+use frame_support::parameter_types;
+use sp_runtime::{
+	traits::{AccountIdConversion, BlakeTwo256, IdentityLookup}, testing::Header, ModuleId,
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		WelcomeBonus: pallet_welcome_bonus::{Module, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = WelcomeBonus;
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const Bonus: u64 = 10;
+	pub const MaxBonusesPerBlock: u32 = 2;
+	pub const WelcomeBonusModuleId: ModuleId = ModuleId(*b"py/wlcme");
+}
+
+impl pallet_welcome_bonus::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type Bonus = Bonus;
+	type MaxBonusesPerBlock = MaxBonusesPerBlock;
+	type ModuleId = WelcomeBonusModuleId;
+	type WeightInfo = ();
+}
+
+/// An account endowed at genesis.
+pub const RICH: u64 = 1;
+
+/// The pot the bonuses are paid from.
+pub fn pot() -> u64 {
+	WelcomeBonusModuleId::get().into_account()
+}
+
+// Build genesis storage according to the mock runtime, with `pot_balance` in the pot.
+pub fn new_test_ext(pot_balance: u64) -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(RICH, 1_000), (pot(), pot_balance)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
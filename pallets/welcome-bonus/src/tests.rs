@@ -0,0 +1,102 @@
+use crate::{mock::*, weights::WeightInfo, RawEvent};
+use frame_support::{assert_ok, traits::OnFinalize, weights::DispatchClass};
+
+fn create_account(who: u64) {
+	assert_ok!(Balances::transfer(Origin::signed(RICH), who, 5));
+}
+
+fn finalize_block() {
+	WelcomeBonus::on_finalize(System::block_number());
+}
+
+fn bonus_paid(who: u64) -> bool {
+	System::events().iter().any(|record| record.event == RawEvent::BonusPaid(who, 10).into())
+}
+
+#[test]
+fn new_accounts_are_paid_when_the_block_is_finalized() {
+	new_test_ext(100).execute_with(|| {
+		create_account(2);
+		assert_eq!(WelcomeBonus::newcomers(), vec![2]);
+		assert_eq!(Balances::free_balance(2), 5);
+
+		finalize_block();
+		assert_eq!(Balances::free_balance(2), 15);
+		assert_eq!(Balances::free_balance(pot()), 90);
+		assert!(bonus_paid(2));
+		assert!(WelcomeBonus::newcomers().is_empty());
+
+		// Existing accounts are not paid again.
+		create_account(2);
+		finalize_block();
+		assert_eq!(Balances::free_balance(2), 20);
+	});
+}
+
+#[test]
+fn genesis_accounts_are_not_paid() {
+	new_test_ext(100).execute_with(|| {
+		assert!(WelcomeBonus::newcomers().is_empty());
+		finalize_block();
+		assert_eq!(Balances::free_balance(RICH), 1_000);
+	});
+}
+
+#[test]
+fn bonuses_per_block_are_capped() {
+	new_test_ext(100).execute_with(|| {
+		create_account(2);
+		create_account(3);
+		create_account(4);
+		assert_eq!(WelcomeBonus::newcomers(), vec![2, 3]);
+
+		finalize_block();
+		assert_eq!(Balances::free_balance(3), 15);
+		assert_eq!(Balances::free_balance(4), 5);
+	});
+}
+
+#[test]
+fn only_recorded_bonuses_are_weighed() {
+	new_test_ext(100).execute_with(|| {
+		let mandatory = || *System::block_weight().get(DispatchClass::Mandatory);
+		let bonus = <() as WeightInfo>::on_finalize(1) - <() as WeightInfo>::on_finalize(0);
+		assert_eq!(mandatory(), 0);
+
+		create_account(2);
+		assert_eq!(mandatory(), bonus);
+
+		// Neither existing accounts nor accounts past the cap add weight.
+		create_account(2);
+		create_account(3);
+		create_account(4);
+		assert_eq!(mandatory(), 2 * bonus);
+	});
+}
+
+#[test]
+fn no_bonus_is_paid_from_an_exhausted_pot() {
+	new_test_ext(15).execute_with(|| {
+		create_account(2);
+		create_account(3);
+		finalize_block();
+
+		assert_eq!(Balances::free_balance(2), 15);
+		assert_eq!(Balances::free_balance(3), 5);
+		assert!(!bonus_paid(3));
+		assert_eq!(Balances::free_balance(pot()), 5);
+	});
+}
+
+#[test]
+fn reaped_accounts_are_not_paid() {
+	new_test_ext(100).execute_with(|| {
+		create_account(2);
+		assert_ok!(Balances::transfer(Origin::signed(2), RICH, 5));
+		assert!(!System::account_exists(&2));
+
+		finalize_block();
+		assert!(!System::account_exists(&2));
+		assert_eq!(Balances::free_balance(pot()), 100);
+	});
+}
@@ -0,0 +1,241 @@
+//! Conversions between human-readable amounts, such as `12.5 UNIT`, and raw balances.
+//!
+//! Amounts are in tokens and must name the token symbol, so that `3` is never mistaken for
+//! `3 UNIT`; they may have up to `decimals` decimal places. Only a bare `0` needs no symbol.
+//!
+//! Raw balances are (de)serialized with [`as_string`].
+
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::Balance;
+
+/// The unit balances are shown in: the symbol of the token and the number of decimals of a raw
+/// balance it spans.
+///
+/// It (de)serializes as the `tokenSymbol` and `tokenDecimals` chain spec properties, so it can be
+/// read from `system_properties`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unit {
+	/// Number of decimals of one token.
+	#[serde(rename = "tokenDecimals")]
+	pub decimals: u8,
+	/// Symbol of the token.
+	#[serde(rename = "tokenSymbol")]
+	pub symbol: String,
+}
+
+/// Why an amount could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBalanceError {
+	/// The amount is empty.
+	Empty,
+	/// The amount is not a decimal number.
+	InvalidNumber,
+	/// The amount does not name the token.
+	MissingSymbol,
+	/// The amount names another token.
+	UnknownSymbol(String),
+	/// The amount has more decimal places than the token.
+	TooManyDecimals(u8),
+	/// The amount does not fit in a `Balance`.
+	Overflow,
+}
+
+impl fmt::Display for ParseBalanceError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ParseBalanceError::Empty => write!(f, "Empty amount"),
+			ParseBalanceError::InvalidNumber => write!(f, "Not a decimal number"),
+			ParseBalanceError::MissingSymbol =>
+				write!(f, "Amounts must name the token, e.g. `12.5 UNIT`"),
+			ParseBalanceError::UnknownSymbol(symbol) => write!(f, "Unknown token `{}`", symbol),
+			ParseBalanceError::TooManyDecimals(decimals) =>
+				write!(f, "The token has only {} decimals", decimals),
+			ParseBalanceError::Overflow => write!(f, "Amount too large"),
+		}
+	}
+}
+
+impl std::error::Error for ParseBalanceError {}
+
+impl Unit {
+	/// The unit of a token with `decimals` decimals and symbol `symbol`.
+	pub fn new(decimals: u8, symbol: impl Into<String>) -> Self {
+		Unit { decimals, symbol: symbol.into() }
+	}
+
+	/// Parse `12.5 UNIT`, `12.5UNIT`, `12 UNIT` or a bare `0` into a balance. `_` may separate
+	/// digits.
+	pub fn parse(&self, amount: &str) -> Result<Balance, ParseBalanceError> {
+		let amount = amount.trim();
+		if amount.is_empty() {
+			return Err(ParseBalanceError::Empty)
+		}
+		let split = amount.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))
+			.unwrap_or(amount.len());
+		let (number, symbol) = amount.split_at(split);
+		let symbol = symbol.trim();
+		let (whole, fraction) = match number.find('.') {
+			Some(point) => (&number[..point], &number[point + 1..]),
+			None => (number, ""),
+		};
+		let has_point = number.contains('.');
+		if whole.is_empty() || fraction.contains('.') || (has_point && fraction.is_empty()) {
+			return Err(ParseBalanceError::InvalidNumber)
+		}
+
+		if symbol.is_empty() {
+			// Zero is zero in any unit.
+			return match digits_to_balance(whole.chars())? {
+				0 if !has_point => Ok(0),
+				_ => Err(ParseBalanceError::MissingSymbol),
+			}
+		}
+		if symbol != self.symbol {
+			return Err(ParseBalanceError::UnknownSymbol(symbol.into()))
+		}
+		let fraction: String = fraction.chars().filter(|c| *c != '_').collect();
+		if fraction.len() > self.decimals as usize {
+			return Err(ParseBalanceError::TooManyDecimals(self.decimals))
+		}
+		let padding = std::iter::repeat('0').take(self.decimals as usize - fraction.len());
+		digits_to_balance(whole.chars().chain(fraction.chars()).chain(padding))
+	}
+
+	/// Format `balance` in tokens, without trailing zeros, e.g. `12.5 UNIT`.
+	pub fn format(&self, balance: Balance) -> String {
+		let decimals = self.decimals as usize;
+		let digits = format!("{:0>width$}", balance, width = decimals + 1);
+		let (whole, fraction) = digits.split_at(digits.len() - decimals);
+		let fraction = fraction.trim_end_matches('0');
+		if fraction.is_empty() {
+			format!("{} {}", whole, self.symbol)
+		} else {
+			format!("{}.{} {}", whole, fraction, self.symbol)
+		}
+	}
+}
+
+/// The balance of decimal digits, ignoring `_` separators.
+fn digits_to_balance(digits: impl Iterator<Item = char>) -> Result<Balance, ParseBalanceError> {
+	digits.filter(|c| *c != '_').try_fold(0 as Balance, |balance, c| {
+		let digit = c.to_digit(10).ok_or(ParseBalanceError::InvalidNumber)?;
+		balance.checked_mul(10)
+			.and_then(|balance| balance.checked_add(digit.into()))
+			.ok_or(ParseBalanceError::Overflow)
+	})
+}
+
+/// (De)serialize a `Balance` as a decimal string, e.g. `#[serde(with = "as_string")]`.
+///
+/// JSON numbers cannot hold every `u128` in most clients, so raw balances are exchanged as
+/// strings; numbers are still accepted when deserializing.
+pub mod as_string {
+	use serde::{de::Error, Deserialize, Deserializer, Serializer};
+	use crate::Balance;
+
+	/// Serialize `balance` as a decimal string.
+	pub fn serialize<S: Serializer>(balance: &Balance, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(balance)
+	}
+
+	/// Deserialize a balance from a decimal string or a number.
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum StringOrNumber {
+			String(String),
+			Number(u64),
+		}
+
+		match StringOrNumber::deserialize(deserializer)? {
+			StringOrNumber::String(s) => s.parse().map_err(D::Error::custom),
+			StringOrNumber::Number(n) => Ok(n.into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn unit() -> Unit {
+		Unit::new(14, "UNIT")
+	}
+
+	#[test]
+	fn parses_amounts_in_tokens() {
+		assert_eq!(unit().parse("12.5 UNIT"), Ok(1_250_000_000_000_000));
+		assert_eq!(unit().parse("12.5UNIT"), Ok(1_250_000_000_000_000));
+		assert_eq!(unit().parse(" 1_000 UNIT "), Ok(100_000_000_000_000_000));
+		assert_eq!(unit().parse("0.00000000000001 UNIT"), Ok(1));
+		assert_eq!(unit().parse("0"), Ok(0));
+	}
+
+	#[test]
+	fn bare_integers_are_not_raw_balances() {
+		assert_eq!(unit().parse("3"), Err(ParseBalanceError::MissingSymbol));
+		assert_eq!(unit().parse("1_000"), Err(ParseBalanceError::MissingSymbol));
+		assert_eq!(unit().parse("0.0"), Err(ParseBalanceError::MissingSymbol));
+	}
+
+	#[test]
+	fn rejects_invalid_amounts() {
+		assert_eq!(unit().parse(""), Err(ParseBalanceError::Empty));
+		assert_eq!(unit().parse("12.5"), Err(ParseBalanceError::MissingSymbol));
+		assert_eq!(unit().parse("12 DOT"), Err(ParseBalanceError::UnknownSymbol("DOT".into())));
+		assert_eq!(unit().parse(".5 UNIT"), Err(ParseBalanceError::InvalidNumber));
+		assert_eq!(unit().parse("1.2.3 UNIT"), Err(ParseBalanceError::InvalidNumber));
+		assert_eq!(unit().parse("3. UNIT"), Err(ParseBalanceError::InvalidNumber));
+		assert_eq!(unit().parse("3.UNIT"), Err(ParseBalanceError::InvalidNumber));
+		assert_eq!(unit().parse("-1"), Err(ParseBalanceError::InvalidNumber));
+		assert_eq!(
+			unit().parse("0.000000000000001 UNIT"),
+			Err(ParseBalanceError::TooManyDecimals(14)),
+		);
+		assert_eq!(
+			unit().parse("340282366920938463463374607431768211456"),
+			Err(ParseBalanceError::Overflow),
+		);
+		assert_eq!(unit().parse("4000000000000000000000000 UNIT"), Err(ParseBalanceError::Overflow));
+	}
+
+	#[test]
+	fn formats_balances() {
+		assert_eq!(unit().format(1_250_000_000_000_000), "12.5 UNIT");
+		assert_eq!(unit().format(100_000_000_000_000), "1 UNIT");
+		assert_eq!(unit().format(1), "0.00000000000001 UNIT");
+		assert_eq!(unit().format(0), "0 UNIT");
+		assert_eq!(Unit::new(0, "X").format(42), "42 X");
+	}
+
+	#[test]
+	fn formatted_balances_parse_back() {
+		for balance in &[0, 1, 12_345, 1_250_000_000_000_000, Balance::max_value()] {
+			assert_eq!(unit().parse(&unit().format(*balance)), Ok(*balance));
+		}
+	}
+
+	#[test]
+	fn balances_are_serialized_as_strings() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Fee {
+			#[serde(with = "as_string")]
+			amount: Balance,
+		}
+
+		let fee = Fee { amount: Balance::max_value() };
+		let json = serde_json::to_string(&fee).unwrap();
+		assert_eq!(json, r#"{"amount":"340282366920938463463374607431768211455"}"#);
+		assert_eq!(serde_json::from_str::<Fee>(&json).unwrap(), fee);
+		assert_eq!(serde_json::from_str::<Fee>(r#"{"amount":42}"#).unwrap(), Fee { amount: 42 });
+		assert!(serde_json::from_str::<Fee>(r#"{"amount":"12.5 UNIT"}"#).is_err());
+		assert!(serde_json::from_str::<Fee>(r#"{"amount":-1}"#).is_err());
+	}
+
+	#[test]
+	fn deserializes_chain_properties() {
+		let properties = r#"{"ss58Format":42,"tokenDecimals":14,"tokenSymbol":"UNIT"}"#;
+		assert_eq!(serde_json::from_str::<Unit>(properties).unwrap(), unit());
+	}
+}
@@ -25,6 +25,9 @@ use sp_runtime::{
 	generic, traits::{Verify, BlakeTwo256, IdentifyAccount}, OpaqueExtrinsic, MultiSignature
 };
 
+#[cfg(feature = "std")]
+pub mod balance;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -0,0 +1,57 @@
+//! A stand-in remote signer for tests and development.
+//!
+//! Serves the keys of a local keystore to nodes started with
+//! `--keystore-uri http://<listen address> --keystore-token-file <token file>`. It serves plain
+//! HTTP on a loopback address; nodes on other hosts reach it through a TLS-terminating proxy.
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use sc_keystore::LocalKeystore;
+use sp_core::crypto::KeyTypeId;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "remote-signer", about = "A stand-in remote signer for tests and development.")]
+struct Opt {
+	/// Address to serve signing requests on; only loopback addresses are accepted.
+	#[structopt(long, default_value = "127.0.0.1:9955")]
+	listen: SocketAddr,
+
+	/// File holding the bearer token that nodes must present.
+	#[structopt(long, parse(from_os_str))]
+	token_file: PathBuf,
+
+	/// Keystore directory. Keys are kept in memory if not given.
+	#[structopt(long, parse(from_os_str))]
+	keystore_path: Option<PathBuf>,
+
+	/// Generate the Aura (sr25519) and GRANDPA (ed25519) keys of a development account, e.g.
+	/// `Alice`, at startup.
+	#[structopt(long)]
+	dev_authority: Option<String>,
+}
+
+fn main() -> Result<(), String> {
+	let opt = Opt::from_args();
+
+	let keystore: SyncCryptoStorePtr = match &opt.keystore_path {
+		Some(path) => Arc::new(LocalKeystore::open(path, None).map_err(|e| e.to_string())?),
+		None => Arc::new(LocalKeystore::in_memory()),
+	};
+
+	if let Some(name) = &opt.dev_authority {
+		let seed = format!("//{}", name);
+		SyncCryptoStore::sr25519_generate_new(&*keystore, KeyTypeId(*b"aura"), Some(&seed))
+			.map_err(|e| e.to_string())?;
+		SyncCryptoStore::ed25519_generate_new(&*keystore, KeyTypeId(*b"gran"), Some(&seed))
+			.map_err(|e| e.to_string())?;
+	}
+
+	let token = std::fs::read_to_string(&opt.token_file)
+		.map_err(|e| format!("Error reading {}: {}", opt.token_file.display(), e))?;
+	let server = remote_keystore::signer::start(&opt.listen, keystore, token.trim().to_string())
+		.map_err(|e| format!("Error serving on {}: {}", opt.listen, e))?;
+	println!("Serving signing requests on http://{}", server.address());
+	server.wait();
+	Ok(())
+}
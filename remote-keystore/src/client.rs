@@ -0,0 +1,551 @@
+//! `CryptoStore` and `SyncCryptoStore` forwarding to a remote signer.
+
+use std::{
+	convert::TryFrom,
+	net::ToSocketAddrs,
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
+	time::Duration,
+};
+use async_trait::async_trait;
+use codec::Decode;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData},
+	CryptoStore, Error, SyncCryptoStore,
+};
+use crate::protocol::{self, error_code, PublicPair};
+
+/// How long to wait for a connection to the signer.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long to wait for the signer to answer a request, well within an Aura slot.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How `RemoteKeystore` and the signer authenticate each other.
+#[derive(Clone, Default)]
+pub struct Credentials {
+	/// Bearer token sent with every request, see [`protocol`](crate::protocol).
+	pub token: String,
+	/// PEM certificates of the authorities that the TLS certificate of the signer must be issued
+	/// by, e.g. a private CA. The web PKI roots are trusted if not given.
+	pub ca_certificates: Option<Vec<u8>>,
+}
+
+impl std::fmt::Debug for Credentials {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		// The token is a secret, keep it out of logs.
+		f.debug_struct("Credentials")
+			.field("token", &"<redacted>")
+			.field("ca_certificates", &self.ca_certificates.is_some())
+			.finish()
+	}
+}
+
+/// A keystore whose keys are held, and whose signatures are made, by a remote signer.
+#[derive(Clone)]
+pub struct RemoteKeystore {
+	url: String,
+	authorization: String,
+	agent: ureq::Agent,
+	next_id: Arc<AtomicU64>,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+	result: Option<T>,
+	error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+	code: i64,
+	message: String,
+}
+
+impl RemoteKeystore {
+	/// A keystore for the signer at `url`, checking that the signer can be reached and accepts
+	/// `credentials`.
+	///
+	/// `url` must be an `https://` URL, e.g. `https://signer.example:9955`. Plain `http://` is only
+	/// accepted for signers on the validator host itself, such as the stand-in `remote-signer`.
+	pub fn connect(url: &str, credentials: &Credentials) -> Result<Self, Error> {
+		ensure_secure(url)?;
+		if credentials.token.is_empty() {
+			return Err(Error::ValidationError("The bearer token of the signer is empty".into()))
+		}
+		let mut agent = ureq::AgentBuilder::new()
+			.timeout_connect(CONNECT_TIMEOUT)
+			.timeout_read(REQUEST_TIMEOUT)
+			.timeout_write(REQUEST_TIMEOUT);
+		if let Some(pem) = &credentials.ca_certificates {
+			agent = agent.tls_config(Arc::new(tls_config(pem)?));
+		}
+		let keystore = RemoteKeystore {
+			url: url.to_string(),
+			authorization: format!("Bearer {}", credentials.token),
+			agent: agent.build(),
+			next_id: Arc::new(AtomicU64::new(0)),
+		};
+		keystore.remote_keys(KeyTypeId(*b"aura"))?;
+		Ok(keystore)
+	}
+
+	/// Call `method` of the signer.
+	fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Error> {
+		let request = json!({
+			"jsonrpc": "2.0",
+			"id": self.next_id.fetch_add(1, Ordering::Relaxed),
+			"method": method,
+			"params": params,
+		});
+		let response: Response<T> = self.agent.post(&self.url)
+			.set("Authorization", &self.authorization)
+			.send_json(request)
+			.map_err(|e| match e {
+				ureq::Error::Status(401, _) => Error::Other(format!(
+					"The signer at {} rejected the bearer token", self.url,
+				)),
+				e => {
+					log::warn!(
+						target: "remote-keystore",
+						"Calling {} on {} failed: {}", method, self.url, e,
+					);
+					Error::Unavailable
+				},
+			})?
+			.into_json()
+			.map_err(|e| Error::Other(format!("Invalid response to {}: {}", method, e)))?;
+
+		match response {
+			Response { result: Some(result), error: None } => Ok(result),
+			Response { error: Some(ResponseError { code, message }), .. } => Err(match code {
+				error_code::PAIR_NOT_FOUND => Error::PairNotFound(message),
+				error_code::VALIDATION_ERROR => Error::ValidationError(message),
+				_ => Error::Other(message),
+			}),
+			_ => Err(Error::Other(format!("Invalid response to {}", method))),
+		}
+	}
+
+	fn remote_keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let keys: Vec<PublicPair> =
+			self.call(protocol::KEYS, json!([protocol::key_type_to_string(id)]))?;
+		keys.into_iter()
+			.map(|pair| CryptoTypePublicPair::try_from(pair).map_err(Error::ValidationError))
+			.collect()
+	}
+
+	/// The keys of `id` and `crypto`; none if the signer cannot be reached.
+	fn public_keys<P: Decode>(&self, id: KeyTypeId, crypto: CryptoTypeId) -> Vec<P> {
+		self.remote_keys(id)
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|pair| pair.0 == crypto)
+			.filter_map(|pair| decode_public(&pair.1).ok())
+			.collect()
+	}
+
+	/// Generate a random key on the signer; keys cannot be generated from a seed, as the seed
+	/// would have to be sent to the signer.
+	fn generate_new<P: Decode>(
+		&self,
+		id: KeyTypeId,
+		crypto: CryptoTypeId,
+		seed: Option<&str>,
+	) -> Result<P, Error> {
+		if seed.is_some() {
+			return Err(Error::ValidationError(
+				"Keys cannot be generated from a seed through the remote keystore, \
+				generate them on the signer instead".into()))
+		}
+		let public: Bytes = self.call(protocol::GENERATE, json!([
+			protocol::key_type_to_string(id),
+			protocol::crypto_to_string(crypto),
+		]))?;
+		decode_public(&public)
+	}
+
+	/// Run `f` on a thread that may block, so that waiting on the signer does not stall the
+	/// executor running Aura and GRANDPA.
+	async fn blocking<R, F>(&self, f: F) -> R
+	where
+		R: Send + 'static,
+		F: FnOnce(&Self) -> R + Send + 'static,
+	{
+		let keystore = self.clone();
+		tokio::task::spawn_blocking(move || f(&keystore))
+			.await
+			.expect("calls to the signer do not panic; qed")
+	}
+}
+
+/// Check that `url` is an `https://` URL, or an `http://` URL whose host resolves only to
+/// loopback addresses.
+fn ensure_secure(url: &str) -> Result<(), Error> {
+	let invalid = || Error::ValidationError(format!(
+		"`{}` is neither an `https://` URL nor an `http://` URL of a signer on a loopback \
+		address",
+		url,
+	));
+	if url.starts_with("https://") {
+		return Ok(())
+	}
+	let authority = url.strip_prefix("http://")
+		.and_then(|rest| rest.split('/').next())
+		.ok_or_else(invalid)?;
+	let mut addrs = authority.to_socket_addrs().map_err(|_| invalid())?.peekable();
+	if addrs.peek().is_none() || !addrs.all(|addr| addr.ip().is_loopback()) {
+		return Err(invalid())
+	}
+	Ok(())
+}
+
+/// A TLS config trusting only the authorities of `pem`.
+fn tls_config(pem: &[u8]) -> Result<rustls::ClientConfig, Error> {
+	let mut config = rustls::ClientConfig::new();
+	match config.root_store.add_pem_file(&mut &pem[..]) {
+		Ok((valid, 0)) if valid > 0 => Ok(config),
+		_ => Err(Error::ValidationError("Invalid CA certificates of the signer".into())),
+	}
+}
+
+fn decode_public<P: Decode>(mut bytes: &[u8]) -> Result<P, Error> {
+	let public = P::decode(&mut bytes)
+		.map_err(|_| Error::ValidationError("Invalid public key".into()))?;
+	if !bytes.is_empty() {
+		return Err(Error::ValidationError("Invalid public key".into()))
+	}
+	Ok(public)
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(id, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		self.generate_new(id, sr25519::CRYPTO_ID, seed)
+	}
+
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(id, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		self.generate_new(id, ed25519::CRYPTO_ID, seed)
+	}
+
+	fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(id, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		self.generate_new(id, ecdsa::CRYPTO_ID, seed)
+	}
+
+	fn insert_unknown(&self, _key_type: KeyTypeId, _suri: &str, _public: &[u8]) -> Result<(), ()> {
+		// Secrets never pass through the validator host, insert keys on the signer instead.
+		Err(())
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let remote = self.remote_keys(id)?;
+		Ok(keys.into_iter().filter(|key| remote.contains(key)).collect())
+	}
+
+	fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.remote_keys(id)
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys.iter()
+			.map(|(public, id)| (Bytes(public.clone()), protocol::key_type_to_string(*id)))
+			.collect::<Vec<_>>();
+		self.call(protocol::HAS_KEYS, json!([keys])).unwrap_or(false)
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		let signature: Bytes = self.call(protocol::SIGN, json!([
+			protocol::key_type_to_string(id),
+			PublicPair::from(key),
+			Bytes(msg.to_vec()),
+		]))?;
+		Ok(signature.0)
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_transcript_data: VRFTranscriptData,
+	) -> Result<VRFSignature, Error> {
+		Err(Error::Other("VRF signing is not supported by the remote keystore".into()))
+	}
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.blocking(move |keystore| SyncCryptoStore::sr25519_public_keys(keystore, id)).await
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		let seed = seed.map(str::to_owned);
+		self.blocking(move |keystore| {
+			SyncCryptoStore::sr25519_generate_new(keystore, id, seed.as_deref())
+		}).await
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.blocking(move |keystore| SyncCryptoStore::ed25519_public_keys(keystore, id)).await
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		let seed = seed.map(str::to_owned);
+		self.blocking(move |keystore| {
+			SyncCryptoStore::ed25519_generate_new(keystore, id, seed.as_deref())
+		}).await
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.blocking(move |keystore| SyncCryptoStore::ecdsa_public_keys(keystore, id)).await
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		let seed = seed.map(str::to_owned);
+		self.blocking(move |keystore| {
+			SyncCryptoStore::ecdsa_generate_new(keystore, id, seed.as_deref())
+		}).await
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		SyncCryptoStore::insert_unknown(self, id, suri, public)
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.blocking(move |keystore| SyncCryptoStore::supported_keys(keystore, id, keys)).await
+	}
+
+	async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.blocking(move |keystore| SyncCryptoStore::keys(keystore, id)).await
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let public_keys = public_keys.to_vec();
+		self.blocking(move |keystore| SyncCryptoStore::has_keys(keystore, &public_keys)).await
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		let (key, msg) = (key.clone(), msg.to_vec());
+		self.blocking(move |keystore| SyncCryptoStore::sign_with(keystore, id, &key, &msg)).await
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> Result<VRFSignature, Error> {
+		SyncCryptoStore::sr25519_vrf_sign(self, key_type, public, transcript_data)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use codec::Encode;
+	use sc_keystore::LocalKeystore;
+	use sp_core::{Pair, crypto::Public};
+
+	const AURA: KeyTypeId = KeyTypeId(*b"aura");
+	const GRANDPA: KeyTypeId = KeyTypeId(*b"gran");
+	const TOKEN: &str = "s3cr3t";
+
+	fn credentials(token: &str) -> Credentials {
+		Credentials { token: token.into(), ca_certificates: None }
+	}
+
+	fn with_signer(test: impl FnOnce(RemoteKeystore)) {
+		let server = crate::signer::start(
+			&"127.0.0.1:0".parse().unwrap(),
+			Arc::new(LocalKeystore::in_memory()),
+			TOKEN.into(),
+		).unwrap();
+		let url = format!("http://{}", server.address());
+		test(RemoteKeystore::connect(&url, &credentials(TOKEN)).unwrap());
+		server.close();
+	}
+
+	#[test]
+	fn connecting_requires_a_reachable_signer() {
+		assert!(RemoteKeystore::connect("http://127.0.0.1:1", &credentials(TOKEN)).is_err());
+		assert!(matches!(
+			RemoteKeystore::connect("https://127.0.0.1:1", &credentials(TOKEN)),
+			Err(Error::Unavailable),
+		));
+	}
+
+	#[test]
+	fn connecting_requires_tls_to_other_hosts() {
+		for url in &["http://10.0.0.1:9955", "127.0.0.1:9955", "http://127.0.0.1"] {
+			let keystore = RemoteKeystore::connect(url, &credentials(TOKEN));
+			assert!(matches!(keystore, Err(Error::ValidationError(_))), "{}", url);
+		}
+	}
+
+	#[test]
+	fn connecting_requires_the_token_of_the_signer() {
+		let server = crate::signer::start(
+			&"127.0.0.1:0".parse().unwrap(),
+			Arc::new(LocalKeystore::in_memory()),
+			TOKEN.into(),
+		).unwrap();
+		let url = format!("http://{}", server.address());
+		assert!(matches!(
+			RemoteKeystore::connect(&url, &credentials("guess")),
+			Err(Error::Other(_)),
+		));
+		assert!(matches!(
+			RemoteKeystore::connect(&url, &credentials("")),
+			Err(Error::ValidationError(_)),
+		));
+		assert!(matches!(
+			RemoteKeystore::connect(&url, &Credentials {
+				token: TOKEN.into(),
+				ca_certificates: Some(b"not a certificate".to_vec()),
+			}),
+			Err(Error::ValidationError(_)),
+		));
+		server.close();
+	}
+
+	#[test]
+	fn unresponsive_signers_time_out() {
+		// Connections are accepted by the listener's backlog but never answered.
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let started = std::time::Instant::now();
+		assert!(matches!(
+			RemoteKeystore::connect(
+				&format!("http://{}", listener.local_addr().unwrap()),
+				&credentials(TOKEN),
+			),
+			Err(Error::Unavailable),
+		));
+		assert!(started.elapsed() < REQUEST_TIMEOUT * 2);
+	}
+
+	#[test]
+	fn generates_and_lists_keys() {
+		with_signer(|keystore| {
+			let aura = SyncCryptoStore::sr25519_generate_new(&keystore, AURA, None).unwrap();
+			let grandpa = SyncCryptoStore::ed25519_generate_new(&keystore, GRANDPA, None).unwrap();
+
+			assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, AURA), vec![aura]);
+			assert!(SyncCryptoStore::ed25519_public_keys(&keystore, AURA).is_empty());
+			assert_eq!(SyncCryptoStore::ed25519_public_keys(&keystore, GRANDPA), vec![grandpa]);
+
+			assert!(SyncCryptoStore::has_keys(&keystore, &[(aura.to_raw_vec(), AURA)]));
+			assert!(!SyncCryptoStore::has_keys(&keystore, &[(aura.to_raw_vec(), GRANDPA)]));
+		});
+	}
+
+	#[test]
+	fn async_calls_run_off_the_executor() {
+		with_signer(|keystore| {
+			let mut runtime = tokio::runtime::Builder::new()
+				.basic_scheduler()
+				.enable_all()
+				.build()
+				.unwrap();
+			runtime.block_on(async {
+				let aura = CryptoStore::sr25519_generate_new(&keystore, AURA, None).await.unwrap();
+				assert_eq!(CryptoStore::sr25519_public_keys(&keystore, AURA).await, vec![aura]);
+				let signature =
+					CryptoStore::sign_with(&keystore, AURA, &aura.into(), b"block").await.unwrap();
+				let signature = sr25519::Signature::decode(&mut &signature[..]).unwrap();
+				assert!(sr25519::Pair::verify(&signature, b"block", &aura));
+			});
+		});
+	}
+
+	#[test]
+	fn signs_with_remote_keys() {
+		with_signer(|keystore| {
+			let aura = SyncCryptoStore::sr25519_generate_new(&keystore, AURA, None).unwrap();
+			let signature = SyncCryptoStore::sign_with(
+				&keystore, AURA, &aura.into(), b"block",
+			).unwrap();
+			let signature = sr25519::Signature::decode(&mut &signature[..]).unwrap();
+			assert!(sr25519::Pair::verify(&signature, b"block", &aura));
+
+			let unknown = ed25519::Pair::from_string("//Bob", None).unwrap().public();
+			assert!(matches!(
+				SyncCryptoStore::sign_with(&keystore, GRANDPA, &unknown.into(), b"vote"),
+				Err(Error::PairNotFound(_)),
+			));
+		});
+	}
+
+	#[test]
+	fn secrets_are_never_sent_to_the_signer() {
+		with_signer(|keystore| {
+			let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+			assert_eq!(
+				SyncCryptoStore::insert_unknown(&keystore, AURA, "//Alice", &alice.encode()),
+				Err(()),
+			);
+			assert!(matches!(
+				SyncCryptoStore::sr25519_generate_new(&keystore, AURA, Some("//Alice")),
+				Err(Error::ValidationError(_)),
+			));
+			assert!(SyncCryptoStore::sr25519_public_keys(&keystore, AURA).is_empty());
+		});
+	}
+}
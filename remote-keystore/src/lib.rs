@@ -0,0 +1,28 @@
+//! A keystore whose keys live with an external signer.
+//!
+//! `RemoteKeystore` implements `CryptoStore` and `SyncCryptoStore` by forwarding every request
+//! to a signer over JSON-RPC, so that a validator can author and vote without its Aura and GRANDPA
+//! keys ever touching the validator host. The node uses it when started with
+//! `--keystore-uri https://<signer>` and `--keystore-token-file <file>`.
+//!
+//! The protocol is described in [`protocol`]. `remote-signer`, built from this crate, is a
+//! stand-in signer backed by a local keystore; it is meant for tests and development, a production
+//! signer would typically front an HSM.
+//!
+//! Keys cannot be inserted through the remote keystore (e.g. with `author_insertKey`), nor
+//! generated from a seed, as that would send their secrets through the validator host: they are
+//! generated at random through it, or inserted on the signer itself. VRF signing is not supported,
+//! neither Aura nor GRANDPA need it.
+//!
+//! The signer is reached over TLS and authenticates the node by a bearer token, see [`protocol`];
+//! plain HTTP is only accepted for a signer on the validator host. Requests time out after a
+//! couple of seconds, and the async `CryptoStore` methods make them on a blocking thread, so an
+//! unresponsive signer makes the validator miss slots and votes rather than hang.
+
+#![warn(missing_docs)]
+
+mod client;
+pub mod protocol;
+pub mod signer;
+
+pub use client::{Credentials, RemoteKeystore};
@@ -0,0 +1,98 @@
+//! The JSON-RPC 2.0 protocol spoken between `RemoteKeystore` and a signer, over HTTPS.
+//!
+//! Every request carries an `Authorization: Bearer <token>` header, with a token shared by the
+//! signer and its validators; signers answer requests without it with HTTP status 401.
+//! `RemoteKeystore` verifies the TLS certificate of the signer, against a private CA if one is
+//! configured. Plain HTTP is only accepted for signers on the validator host, e.g. the stand-in
+//! `remote-signer`. Secrets are never part of the protocol.
+//!
+//! Key types (`aura`, `gran`, ...) and crypto types (`sr25`, `ed25`, `ecds`) are sent as their
+//! four ASCII characters, public keys, messages and signatures as hex strings.
+//!
+//! | Method                 | Params                               | Result                  |
+//! |------------------------|--------------------------------------|-------------------------|
+//! | `signer_keys`          | `key_type`                           | `[PublicPair]`          |
+//! | `signer_generate`      | `key_type`, `crypto`                 | public key              |
+//! | `signer_hasKeys`       | `[[public key, key_type]]`           | `bool`                  |
+//! | `signer_sign`          | `key_type`, `PublicPair`, `message`  | signature               |
+//!
+//! Failures are reported as JSON-RPC errors with one of the codes in [`error_code`].
+
+use serde::{Deserialize, Serialize};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+	Bytes,
+};
+use std::convert::TryFrom;
+
+/// Method listing the keys of a key type.
+pub const KEYS: &str = "signer_keys";
+/// Method generating a new key.
+pub const GENERATE: &str = "signer_generate";
+/// Method checking that all of the given keys exist.
+pub const HAS_KEYS: &str = "signer_hasKeys";
+/// Method signing a message.
+pub const SIGN: &str = "signer_sign";
+
+/// Codes of the errors returned by a signer.
+pub mod error_code {
+	/// The requested key does not exist.
+	pub const PAIR_NOT_FOUND: i64 = 1;
+	/// The crypto type is not supported.
+	pub const KEY_NOT_SUPPORTED: i64 = 2;
+	/// The request is malformed.
+	pub const VALIDATION_ERROR: i64 = 3;
+	/// Any other failure.
+	pub const OTHER: i64 = 4;
+}
+
+/// A public key together with its crypto type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicPair {
+	/// The crypto type, e.g. `sr25`.
+	pub crypto: String,
+	/// The public key.
+	pub public: Bytes,
+}
+
+impl From<&CryptoTypePublicPair> for PublicPair {
+	fn from(pair: &CryptoTypePublicPair) -> Self {
+		PublicPair { crypto: four_cc(&(pair.0).0), public: pair.1.clone().into() }
+	}
+}
+
+impl TryFrom<PublicPair> for CryptoTypePublicPair {
+	type Error = String;
+
+	fn try_from(pair: PublicPair) -> Result<Self, Self::Error> {
+		Ok(CryptoTypePublicPair(CryptoTypeId(parse_four_cc(&pair.crypto)?), pair.public.0))
+	}
+}
+
+/// The four characters of a key type.
+pub fn key_type_to_string(key_type: KeyTypeId) -> String {
+	four_cc(&key_type.0)
+}
+
+/// Parse a key type from its four characters.
+pub fn key_type_from_str(s: &str) -> Result<KeyTypeId, String> {
+	parse_four_cc(s).map(KeyTypeId)
+}
+
+/// The four characters of a crypto type.
+pub fn crypto_to_string(crypto: CryptoTypeId) -> String {
+	four_cc(&crypto.0)
+}
+
+/// Parse a crypto type from its four characters.
+pub fn crypto_from_str(s: &str) -> Result<CryptoTypeId, String> {
+	parse_four_cc(s).map(CryptoTypeId)
+}
+
+fn four_cc(id: &[u8; 4]) -> String {
+	String::from_utf8_lossy(id).into_owned()
+}
+
+fn parse_four_cc(s: &str) -> Result<[u8; 4], String> {
+	<[u8; 4]>::try_from(s.as_bytes()).map_err(|_| format!("`{}` is not four bytes long", s))
+}
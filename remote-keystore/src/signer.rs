@@ -0,0 +1,152 @@
+//! A signer serving the [`protocol`](crate::protocol) from any `SyncCryptoStore`.
+
+use std::{convert::TryFrom, net::SocketAddr};
+use jsonrpc_core::{Error as RpcError, ErrorCode, IoHandler, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_http_server::{
+	hyper::{self, header::{HeaderValue, AUTHORIZATION}, StatusCode},
+	RequestMiddlewareAction, Response,
+};
+use sp_core::{crypto::CryptoTypePublicPair, ecdsa, ed25519, sr25519, Bytes};
+use sp_keystore::{Error as KeystoreError, SyncCryptoStore, SyncCryptoStorePtr};
+use crate::protocol::{self, error_code, PublicPair};
+
+/// The signer side of the protocol.
+#[rpc(server)]
+pub trait SignerApi {
+	/// All keys of `key_type`.
+	#[rpc(name = "signer_keys")]
+	fn keys(&self, key_type: String) -> Result<Vec<PublicPair>>;
+
+	/// Generate a random key of `key_type` and `crypto`.
+	#[rpc(name = "signer_generate")]
+	fn generate(&self, key_type: String, crypto: String) -> Result<Bytes>;
+
+	/// Whether all of `keys` exist.
+	#[rpc(name = "signer_hasKeys")]
+	fn has_keys(&self, keys: Vec<(Bytes, String)>) -> Result<bool>;
+
+	/// Sign `message` with `key` of `key_type`.
+	#[rpc(name = "signer_sign")]
+	fn sign(&self, key_type: String, key: PublicPair, message: Bytes) -> Result<Bytes>;
+}
+
+/// Serves signing requests with the keys of a keystore.
+pub struct Signer {
+	keystore: SyncCryptoStorePtr,
+}
+
+impl Signer {
+	/// A signer for the keys of `keystore`.
+	pub fn new(keystore: SyncCryptoStorePtr) -> Self {
+		Signer { keystore }
+	}
+}
+
+impl SignerApi for Signer {
+	fn keys(&self, key_type: String) -> Result<Vec<PublicPair>> {
+		let key_type = protocol::key_type_from_str(&key_type).map_err(invalid)?;
+		let keys = SyncCryptoStore::keys(&*self.keystore, key_type).map_err(keystore_error)?;
+		Ok(keys.iter().map(PublicPair::from).collect())
+	}
+
+	fn generate(&self, key_type: String, crypto: String) -> Result<Bytes> {
+		let key_type = protocol::key_type_from_str(&key_type).map_err(invalid)?;
+		let keystore = &*self.keystore;
+		let public = match protocol::crypto_from_str(&crypto).map_err(invalid)? {
+			sr25519::CRYPTO_ID => SyncCryptoStore::sr25519_generate_new(keystore, key_type, None)
+				.map(|public| public.0.to_vec()),
+			ed25519::CRYPTO_ID => SyncCryptoStore::ed25519_generate_new(keystore, key_type, None)
+				.map(|public| public.0.to_vec()),
+			ecdsa::CRYPTO_ID => SyncCryptoStore::ecdsa_generate_new(keystore, key_type, None)
+				.map(|public| public.as_ref().to_vec()),
+			_ => Err(KeystoreError::KeyNotSupported(key_type)),
+		}.map_err(keystore_error)?;
+		Ok(public.into())
+	}
+
+	fn has_keys(&self, keys: Vec<(Bytes, String)>) -> Result<bool> {
+		let keys = keys.into_iter()
+			.map(|(public, key_type)| {
+				protocol::key_type_from_str(&key_type).map(|key_type| (public.0, key_type))
+			})
+			.collect::<std::result::Result<Vec<_>, _>>()
+			.map_err(invalid)?;
+		Ok(SyncCryptoStore::has_keys(&*self.keystore, &keys))
+	}
+
+	fn sign(&self, key_type: String, key: PublicPair, message: Bytes) -> Result<Bytes> {
+		let key_type = protocol::key_type_from_str(&key_type).map_err(invalid)?;
+		let key = CryptoTypePublicPair::try_from(key).map_err(invalid)?;
+		SyncCryptoStore::sign_with(&*self.keystore, key_type, &key, &message)
+			.map(Into::into)
+			.map_err(keystore_error)
+	}
+}
+
+/// Start serving `keystore` on `addr` to clients presenting the bearer `token`.
+///
+/// The signer serves plain HTTP, so `addr` must be a loopback address: validators on other hosts
+/// reach it through a TLS-terminating proxy in front of it.
+pub fn start(
+	addr: &SocketAddr,
+	keystore: SyncCryptoStorePtr,
+	token: String,
+) -> std::io::Result<jsonrpc_http_server::Server> {
+	let invalid_input =
+		|message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+	if !addr.ip().is_loopback() {
+		return Err(invalid_input(format!(
+			"{} is not a loopback address, serve other hosts through a TLS proxy", addr,
+		)))
+	}
+	if token.is_empty() {
+		return Err(invalid_input("The bearer token is empty".into()))
+	}
+	let authorization = format!("Bearer {}", token);
+	let mut io = IoHandler::default();
+	io.extend_with(Signer::new(keystore).to_delegate());
+	let authorize = move |request: hyper::Request<hyper::Body>| -> RequestMiddlewareAction {
+		let authorized = request.headers()
+			.get(AUTHORIZATION)
+			.map_or(false, |value| {
+				equal_in_constant_time(value.as_bytes(), authorization.as_bytes())
+			});
+		if authorized {
+			request.into()
+		} else {
+			Response {
+				code: StatusCode::UNAUTHORIZED,
+				content_type: HeaderValue::from_static("text/plain; charset=utf-8"),
+				content: "Missing or invalid bearer token\n".into(),
+			}.into()
+		}
+	};
+	jsonrpc_http_server::ServerBuilder::new(io)
+		.request_middleware(authorize)
+		.start_http(addr)
+}
+
+/// Compare `a` and `b` in a time that does not depend on where they differ, so that the token
+/// cannot be guessed byte by byte.
+fn equal_in_constant_time(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn invalid(message: String) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(error_code::VALIDATION_ERROR),
+		message,
+		data: None,
+	}
+}
+
+fn keystore_error(e: KeystoreError) -> RpcError {
+	let code = match e {
+		KeystoreError::PairNotFound(_) => error_code::PAIR_NOT_FOUND,
+		KeystoreError::KeyNotSupported(_) => error_code::KEY_NOT_SUPPORTED,
+		KeystoreError::ValidationError(_) => error_code::VALIDATION_ERROR,
+		_ => error_code::OTHER,
+	};
+	RpcError { code: ErrorCode::ServerError(code), message: e.to_string(), data: None }
+}
@@ -0,0 +1,269 @@
+//! Arbitrary sequences of calls to our pallets, signed by a few accounts and applied over several
+//! blocks through `Executive`, with fees charged by the real signed extensions.
+//!
+//! No sequence may panic the runtime, and after every block:
+//!
+//! - total issuance is the sum of the free and reserved balances of all accounts;
+//! - every account's reserved balance is the sum of its anchor, commitment and room deposits;
+//! - the per-room anchor counts match the anchors of each room;
+//! - the member counts of rooms match their members, and owners are members of their rooms;
+//! - rooms without a sponsor have an empty fee budget.
+//!
+//! Run with `cargo fuzz run call_sequences` from `runtime/`.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use codec::Encode;
+use frame_support::storage::IterableStorageMap;
+use libfuzzer_sys::fuzz_target;
+use node_template_runtime::{
+	pallet_template, AccountId, AuraConfig, Balance, Balances, BalancesCall, BalancesConfig,
+	BlockNumber, Call, ContentAnchor, ContentAnchorCall, ContentHash, Executive, GenesisConfig,
+	GrandpaConfig, Hash, Header, RandomnessBeacon, RoomId, Rooms, RoomsCall, Runtime, SignedExtra,
+	Sponsorship, SponsorshipCall, SudoConfig, System, SystemCall, SystemConfig, TimestampCall,
+	UncheckedExtrinsic, SLOT_DURATION, node_constants::currency::DOLLARS,
+};
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_keyring::AccountKeyring;
+use sp_runtime::{
+	generic::{Digest, DigestItem, Era, SignedPayload},
+	traits::Header as _,
+	BuildStorage,
+};
+
+/// The signers; the last one has no funds.
+const SIGNERS: [AccountKeyring; 4] = [
+	AccountKeyring::Alice,
+	AccountKeyring::Bob,
+	AccountKeyring::Charlie,
+	AccountKeyring::Dave,
+];
+const INITIAL_BALANCE: Balance = 10_000 * DOLLARS;
+/// Rooms and content hashes are drawn from small domains so that calls collide.
+const ROOMS: u8 = 4;
+const HASHES: u8 = 8;
+const MAX_BLOCKS: usize = 8;
+const MAX_CALLS_PER_BLOCK: usize = 16;
+
+#[derive(Debug, Arbitrary)]
+enum Action {
+	Transfer { to: u8, amount: u64 },
+	Remark,
+	DoSomething(u32),
+	CauseError,
+	OpenRoom,
+	JoinRoom { room: u8 },
+	LeaveRoom { room: u8 },
+	RemoveMember { room: u8, who: u8 },
+	CloseRoom { room: u8 },
+	Fund { room: u8, amount: u64 },
+	Withdraw { room: u8, amount: u64 },
+	StopSponsoring { room: u8 },
+	Anchor { room: u8, hash: u8, size: u64, license_len: u8 },
+	RemoveAnchor { hash: u8 },
+	Commit { secret: u8 },
+	Reveal { secret: u8 },
+}
+
+#[derive(Debug, Arbitrary)]
+struct Signed {
+	signer: u8,
+	tip: u16,
+	action: Action,
+}
+
+fn signer(index: u8) -> AccountKeyring {
+	SIGNERS[index as usize % SIGNERS.len()]
+}
+
+fn room(room: u8) -> RoomId {
+	(room % ROOMS).into()
+}
+
+fn content_hash(hash: u8) -> ContentHash {
+	ContentHash::Blake2_256([hash % HASHES; 32])
+}
+
+fn secret(secret: u8) -> [u8; 32] {
+	[secret; 32]
+}
+
+impl Action {
+	fn call(&self, who: &AccountId) -> Call {
+		match *self {
+			Action::Transfer { to, amount } => Call::Balances(
+				BalancesCall::transfer(signer(to).to_account_id().into(), amount.into())
+			),
+			Action::Remark => Call::System(SystemCall::remark(Vec::new())),
+			Action::DoSomething(value) => {
+				Call::TemplateModule(pallet_template::Call::do_something(value))
+			},
+			Action::CauseError => Call::TemplateModule(pallet_template::Call::cause_error()),
+			Action::OpenRoom => Call::Rooms(RoomsCall::open()),
+			Action::JoinRoom { room: r } => Call::Rooms(RoomsCall::join(room(r))),
+			Action::LeaveRoom { room: r } => Call::Rooms(RoomsCall::leave(room(r))),
+			Action::RemoveMember { room: r, who } => {
+				Call::Rooms(RoomsCall::remove_member(room(r), signer(who).to_account_id()))
+			},
+			Action::CloseRoom { room: r } => Call::Rooms(RoomsCall::close(room(r))),
+			Action::Fund { room: r, amount } => {
+				Call::Sponsorship(SponsorshipCall::fund(room(r), amount.into()))
+			},
+			Action::Withdraw { room: r, amount } => {
+				Call::Sponsorship(SponsorshipCall::withdraw(room(r), amount.into()))
+			},
+			Action::StopSponsoring { room: r } => Call::Sponsorship(SponsorshipCall::stop(room(r))),
+			Action::Anchor { room: r, hash, size, license_len } => Call::ContentAnchor(
+				ContentAnchorCall::anchor(
+					room(r),
+					content_hash(hash),
+					size,
+					vec![b'x'; license_len as usize % 40],
+				)
+			),
+			Action::RemoveAnchor { hash } => {
+				Call::ContentAnchor(ContentAnchorCall::remove(content_hash(hash)))
+			},
+			Action::Commit { secret: s } => Call::RandomnessBeacon(
+				pallet_randomness_beacon::Call::commit(
+					RandomnessBeacon::commitment_of(who, &secret(s))
+				)
+			),
+			Action::Reveal { secret: s } => {
+				Call::RandomnessBeacon(pallet_randomness_beacon::Call::reveal(secret(s)))
+			},
+		}
+	}
+}
+
+fn sign(signer: AccountKeyring, call: Call, tip: Balance) -> UncheckedExtrinsic {
+	let account = signer.to_account_id();
+	let extra: SignedExtra = (
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(Era::Immortal),
+		frame_system::CheckNonce::from(System::account_nonce(&account)),
+		frame_system::CheckWeight::new(),
+		pallet_rate_limit::CheckRateLimit::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::from(tip).into(),
+	);
+	let payload = SignedPayload::new(call, extra).expect("The signed extensions are valid");
+	let signature = payload.using_encoded(|payload| signer.sign(payload));
+	let (call, extra, _) = payload.deconstruct();
+	UncheckedExtrinsic::new_signed(call, account.into(), signature.into(), extra)
+}
+
+fn initialize_block(number: BlockNumber, parent_hash: Hash) {
+	let slot = number as u64;
+	let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+	Executive::initialize_block(&Header::new(
+		number,
+		Default::default(),
+		Default::default(),
+		parent_hash,
+		digest,
+	));
+	let timestamp = UncheckedExtrinsic::new_unsigned(
+		Call::Timestamp(TimestampCall::set(slot * SLOT_DURATION))
+	);
+	Executive::apply_extrinsic(timestamp)
+		.expect("The timestamp inherent is valid")
+		.expect("The timestamp inherent succeeds");
+}
+
+fn check_invariants() {
+	let accounts: Vec<_> = frame_system::Account::<Runtime>::iter().collect();
+	let held: Balance = accounts.iter().map(|(_, info)| info.data.free + info.data.reserved).sum();
+	assert_eq!(Balances::total_issuance(), held, "Total issuance is not held by accounts");
+
+	let anchors: Vec<_> = (0..HASHES)
+		.filter_map(|hash| ContentAnchor::anchor_of(content_hash(hash)))
+		.collect();
+	for (who, info) in &accounts {
+		let anchored: Balance = anchors.iter()
+			.filter(|anchor| &anchor.owner == who)
+			.map(|anchor| anchor.deposit)
+			.sum();
+		let committed = RandomnessBeacon::commitments(who).map_or(0, |c| c.deposit);
+		let opened: Balance = (0..Rooms::next_room_id())
+			.filter_map(Rooms::room)
+			.filter(|info| &info.owner == who)
+			.map(|info| info.deposit)
+			.sum();
+		assert_eq!(
+			info.data.reserved,
+			anchored + committed + opened,
+			"Reserved balance of {} is off",
+			who,
+		);
+	}
+
+	for r in 0..ROOMS {
+		let in_room = anchors.iter().filter(|anchor| anchor.room == room(r)).count() as u32;
+		assert_eq!(ContentAnchor::room_anchor_count(room(r)), in_room, "Anchor count is off");
+		match Rooms::room(room(r)) {
+			Some(info) => {
+				let members = SIGNERS.iter()
+					.filter(|who| Rooms::is_member(&who.to_account_id(), room(r)))
+					.count() as u32;
+				assert_eq!(info.members, members, "Member count is off");
+				assert!(Rooms::is_member(&info.owner, room(r)), "Owner is not a member");
+			},
+			None => assert!(
+				SIGNERS.iter().all(|who| !Rooms::is_member(&who.to_account_id(), room(r))),
+				"Closed room has members",
+			),
+		}
+		if Sponsorship::sponsor(room(r)).is_none() {
+			assert_eq!(Sponsorship::budget(room(r)), 0, "Unsponsored room has a budget");
+		}
+	}
+
+	let open: Vec<_> = (0..Rooms::next_room_id()).filter_map(Rooms::room).collect();
+	assert_eq!(Rooms::room_count(), open.len() as u32, "Room count is off");
+	assert_eq!(
+		Rooms::member_count(),
+		open.iter().map(|info| info.members).sum::<u32>(),
+		"Total member count is off",
+	);
+	let anchored = (0..ROOMS)
+		.filter(|r| anchors.iter().any(|anchor| anchor.room == room(*r)))
+		.count() as u32;
+	assert_eq!(ContentAnchor::anchored_rooms(), anchored, "Anchored room count is off");
+	let sponsored = (0..ROOMS).filter(|r| Sponsorship::sponsor(room(*r)).is_some()).count() as u32;
+	assert_eq!(Sponsorship::sponsored_rooms(), sponsored, "Sponsored room count is off");
+}
+
+fuzz_target!(|blocks: Vec<Vec<Signed>>| {
+	let storage = GenesisConfig {
+		frame_system: Some(SystemConfig {
+			code: Vec::new(),
+			changes_trie_config: Default::default(),
+		}),
+		pallet_balances: Some(BalancesConfig {
+			balances: SIGNERS[..3].iter()
+				.map(|who| (who.to_account_id(), INITIAL_BALANCE))
+				.collect(),
+		}),
+		pallet_aura: Some(AuraConfig { authorities: Vec::new() }),
+		pallet_grandpa: Some(GrandpaConfig { authorities: Vec::new() }),
+		pallet_sudo: Some(SudoConfig { key: AccountKeyring::Alice.to_account_id() }),
+	}.build_storage().expect("The genesis config is valid");
+
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		let mut parent_hash = Hash::default();
+		for (index, calls) in blocks.iter().take(MAX_BLOCKS).enumerate() {
+			initialize_block(index as BlockNumber + 1, parent_hash);
+			for Signed { signer: s, tip, action } in calls.iter().take(MAX_CALLS_PER_BLOCK) {
+				let signer = signer(*s);
+				let call = action.call(&signer.to_account_id());
+				// Invalid and failing extrinsics are expected; only panics are bugs.
+				let _ = Executive::apply_extrinsic(sign(signer, call, (*tip).into()));
+			}
+			parent_hash = Executive::finalize_block().hash();
+			check_invariants();
+		}
+	});
+});
@@ -7,13 +7,14 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use sp_std::prelude::*;
+use codec::{Compact, Decode, Encode};
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature, ModuleId,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-	AccountIdLookup, BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor,
+	AccountIdLookup, BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, StaticLookup,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -26,12 +27,17 @@ use sp_version::NativeVersion;
 // A few exports that help ease life for downstream crates.
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
+pub use frame_system::Call as SystemCall;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_sudo::Call as SudoCall;
+pub use pallet_content_anchor::{Call as ContentAnchorCall, ContentHash};
+pub use pallet_sponsorship::Call as SponsorshipCall;
+pub use pallet_rooms::Call as RoomsCall;
 pub use sp_runtime::{Permill, Perbill};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
-	traits::{KeyOwnerProofSystem, Randomness},
+	traits::{Filter, KeyOwnerProofSystem, Randomness},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -42,6 +48,8 @@ use pallet_transaction_payment::CurrencyAdapter;
 /// Import the template pallet.
 pub use pallet_template;
 pub use node_primitives;
+pub use node_constants;
+use node_constants::{currency::*, prod_or_fast};
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -69,6 +77,9 @@ pub type Hash = sp_core::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// Identifier of a room.
+pub type RoomId = pallet_rooms::RoomId;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -97,27 +108,17 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 100,
+	spec_version: 103,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 
-/// This determines the average expected block time that we are targeting.
-/// Blocks will be produced at a minimum duration defined by `SLOT_DURATION`.
-/// `SLOT_DURATION` is picked up by `pallet_timestamp` which is in turn picked
-/// up by `pallet_aura` to implement `fn slot_duration()`.
-///
-/// Change this to adjust the block time.
-pub const MILLISECS_PER_BLOCK: u64 = 6000;
-
-pub const SLOT_DURATION: u64 = MILLISECS_PER_BLOCK;
-
-// Time is measured by number of blocks.
-pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
-pub const HOURS: BlockNumber = MINUTES * 60;
-pub const DAYS: BlockNumber = HOURS * 24;
+/// The block time and the units of time measured in blocks, shortened by the `fast-runtime`
+/// feature. `SLOT_DURATION` is picked up by `pallet_timestamp` which is in turn picked up by
+/// `pallet_aura` to implement `fn slot_duration()`.
+pub use node_constants::time::{MILLISECS_PER_BLOCK, SLOT_DURATION, MINUTES, HOURS, DAYS};
 
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
@@ -181,9 +182,12 @@ impl frame_system::Config for Runtime {
 	/// This type is being generated by `construct_runtime!`.
 	type PalletInfo = PalletInfo;
 	/// What to do if a new account is created.
-	type OnNewAccount = ();
+	type OnNewAccount = WelcomeBonus;
 	/// What to do if an account is fully reaped from the system.
-	type OnKilledAccount = ();
+	///
+	/// Anchors, commitments, sponsorships and owned rooms hold deposits or funds, which keep their
+	/// accounts alive; the free call and rate limit bookkeeping and joined rooms are cleaned up.
+	type OnKilledAccount = (FreeTx, RateLimit, Rooms);
 	/// The data to be stored in an account.
 	type AccountData = pallet_balances::AccountData<Balance>;
 	/// Weight information for the extrinsics of this pallet.
@@ -228,7 +232,7 @@ impl pallet_timestamp::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u128 = 500;
+	pub const ExistentialDeposit: Balance = CENTS;
 	pub const MaxLocks: u32 = 50;
 }
 
@@ -245,24 +249,202 @@ impl pallet_balances::Config for Runtime {
 }
 
 parameter_types! {
-	pub const TransactionByteFee: Balance = 1;
+	pub const TransactionByteFee: Balance = 10 * MILLICENTS;
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = pallet_sponsorship::SponsoredAdapter<
+		Runtime,
+		CurrencyAdapter<Balances, ()>,
+	>;
 	type TransactionByteFee = TransactionByteFee;
 	type WeightToFee = IdentityFee<Balance>;
 	type FeeMultiplierUpdate = ();
 }
 
+/// Calls every account may make a few times per day without paying fees.
+pub struct FreeCalls;
+impl Filter<Call> for FreeCalls {
+	fn filter(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::Rooms(pallet_rooms::Call::join(..)) | Call::Rooms(pallet_rooms::Call::leave(..))
+		)
+	}
+}
+
+parameter_types! {
+	pub const FreeCallsPerPeriod: u32 = 10;
+	pub const QuotaPeriod: BlockNumber = prod_or_fast!(DAYS, 10 * MINUTES);
+	pub const MaxFreeCallsPerBlock: u32 = 50;
+}
+
+impl pallet_free_tx::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type FreeCalls = FreeCalls;
+	type FreeCallsPerPeriod = FreeCallsPerPeriod;
+	type QuotaPeriod = QuotaPeriod;
+	type MaxFreeCallsPerBlock = MaxFreeCallsPerBlock;
+	type WeightInfo = pallet_free_tx::weights::SubstrateWeight<Runtime>;
+}
+
+/// Room actions an account may only make a limited number of times per window.
+pub struct RateLimitedCalls;
+impl Filter<Call> for RateLimitedCalls {
+	fn filter(call: &Call) -> bool {
+		// Includes every call `CallRoom` maps to a room, so that members cannot spend a sponsor's
+		// budget faster than this.
+		matches!(call, Call::ContentAnchor(..) | Call::Rooms(..))
+	}
+}
+
+parameter_types! {
+	pub const RateLimitWindow: BlockNumber = MINUTES;
+	pub const MaxCallsPerWindow: u32 = 20;
+}
+
+impl pallet_rate_limit::Config for Runtime {
+	type Event = Event;
+	type LimitedCalls = RateLimitedCalls;
+	type Window = RateLimitWindow;
+	type MaxCallsPerWindow = MaxCallsPerWindow;
+	type WeightInfo = pallet_rate_limit::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const CommitDeposit: Balance = DOLLARS;
+	pub const RevealPeriod: BlockNumber = prod_or_fast!(10 * MINUTES, MINUTES);
+	pub const MaxCommitmentsPerBlock: u32 = 100;
+}
+
+/// The runtime-wide randomness source. Pallets that need randomness should take a
+/// `type Randomness: Randomness<Hash>` in their `Config` and be given `RandomnessBeacon`.
+impl pallet_randomness_beacon::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type CommitDeposit = CommitDeposit;
+	type RevealPeriod = RevealPeriod;
+	type MaxCommitmentsPerBlock = MaxCommitmentsPerBlock;
+	type Forfeited = ();
+	type WeightInfo = pallet_randomness_beacon::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const AnchorDepositBase: Balance = deposit(1, 0);
+	pub const AnchorDepositPerByte: Balance = deposit(0, 1);
+	pub const MaxCidLength: u32 = 64;
+	pub const MaxLicenseLength: u32 = 32;
+	pub const MaxAnchorsPerRoom: u32 = 1_000;
+}
+
+impl pallet_content_anchor::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type RoomId = RoomId;
+	type AnchorDepositBase = AnchorDepositBase;
+	type AnchorDepositPerByte = AnchorDepositPerByte;
+	type MaxCidLength = MaxCidLength;
+	type MaxLicenseLength = MaxLicenseLength;
+	type MaxAnchorsPerRoom = MaxAnchorsPerRoom;
+	type WeightInfo = pallet_content_anchor::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	/// Covers the room and the membership of its owner.
+	pub const RoomDeposit: Balance = deposit(2, 64);
+	pub const MaxMembers: u32 = 1_000;
+}
+
+impl pallet_rooms::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type RoomDeposit = RoomDeposit;
+	type MaxMembers = MaxMembers;
+	type WeightInfo = pallet_rooms::weights::SubstrateWeight<Runtime>;
+}
+
+/// The room a call is made in, for fee sponsorship.
+///
+/// Every call mapped to a room must also be in `RateLimitedCalls`.
+pub struct CallRoom;
+impl pallet_sponsorship::CallRoom<Call, RoomId> for CallRoom {
+	fn room_of(call: &Call) -> Option<RoomId> {
+		match call {
+			Call::ContentAnchor(pallet_content_anchor::Call::anchor(room, ..)) => Some(*room),
+			Call::ContentAnchor(pallet_content_anchor::Call::remove(hash)) =>
+				ContentAnchor::anchor_of(hash).map(|anchor| anchor.room),
+			Call::Rooms(pallet_rooms::Call::join(room)) => Some(*room),
+			Call::Rooms(pallet_rooms::Call::leave(room)) => Some(*room),
+			_ => None,
+		}
+	}
+
+	fn joins_room(call: &Call) -> bool {
+		matches!(call, Call::Rooms(pallet_rooms::Call::join(..)))
+	}
+}
+
+/// Room owners sponsor the calls of the members of their rooms.
+pub struct RoomRoles;
+impl pallet_sponsorship::RoomOwnership<AccountId, RoomId> for RoomRoles {
+	fn is_owner(who: &AccountId, room: &RoomId) -> bool {
+		Rooms::is_owner(who, *room)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_owner(who: &AccountId, room: &RoomId) {
+		Rooms::open_for_benchmarks(who, *room)
+	}
+}
+
+impl pallet_sponsorship::RoomMembership<AccountId, RoomId> for RoomRoles {
+	fn is_member(who: &AccountId, room: &RoomId) -> bool {
+		Rooms::is_member(who, *room)
+	}
+}
+
+parameter_types! {
+	pub const SponsorshipModuleId: ModuleId = ModuleId(*b"lstn/spn");
+}
+
+impl pallet_sponsorship::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type RoomId = RoomId;
+	type CallRoom = CallRoom;
+	type RoomOwnership = RoomRoles;
+	type RoomMembership = RoomRoles;
+	type ModuleId = SponsorshipModuleId;
+	type WeightInfo = pallet_sponsorship::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	/// Less than the fee of the transfer creating an account, so that farming it does not pay.
+	pub const NewAccountBonus: Balance = CENTS;
+	pub const MaxBonusesPerBlock: u32 = 20;
+	pub const WelcomeBonusModuleId: ModuleId = ModuleId(*b"lstn/wel");
+}
+
+/// New accounts get a welcome bonus from a pot that is funded by transfers.
+impl pallet_welcome_bonus::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Bonus = NewAccountBonus;
+	type MaxBonusesPerBlock = MaxBonusesPerBlock;
+	type ModuleId = WelcomeBonusModuleId;
+	type WeightInfo = pallet_welcome_bonus::weights::SubstrateWeight<Runtime>;
+}
+
 /// Configure the template pallet in pallets/template.
 impl pallet_template::Config for Runtime {
 	type Event = Event;
+	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -279,9 +461,16 @@ construct_runtime!(
 		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		FreeTx: pallet_free_tx::{Module, Storage, Event<T>},
+		RateLimit: pallet_rate_limit::{Module, Storage, Event<T>},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
+		RandomnessBeacon: pallet_randomness_beacon::{Module, Call, Storage, Event<T>},
+		ContentAnchor: pallet_content_anchor::{Module, Call, Storage, Event<T>},
+		Sponsorship: pallet_sponsorship::{Module, Call, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
+		WelcomeBonus: pallet_welcome_bonus::{Module, Storage, Event<T>},
+		Rooms: pallet_rooms::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -303,7 +492,8 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>
+	pallet_rate_limit::CheckRateLimit<Runtime>,
+	pallet_free_tx::ChargeTransactionPaymentOrFree<Runtime>
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -360,8 +550,11 @@ impl_runtime_apis! {
 			data.check_extrinsics(&block)
 		}
 
+		// Deprecated: the collective flip seed can be biased by block authors. Runtime code
+		// must use the `Randomness` implementation of `RandomnessBeacon` instead; this is only
+		// kept because `BlockBuilder` requires it.
 		fn random_seed() -> <Block as BlockT>::Hash {
-			RandomnessCollectiveFlip::random_seed()
+			RandomnessBeacon::random_seed()
 		}
 	}
 
@@ -450,6 +643,68 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_content_anchor_runtime_api::ContentAnchorApi<Block, AccountId, RoomId, Balance, BlockNumber>
+		for Runtime {
+		fn anchor(
+			hash: pallet_content_anchor::ContentHash,
+		) -> Option<pallet_content_anchor::Anchor<AccountId, RoomId, Balance, BlockNumber>> {
+			ContentAnchor::anchor_of(hash)
+		}
+
+		fn anchors_by_room(
+			room: RoomId,
+		) -> Vec<(
+			pallet_content_anchor::ContentHash,
+			pallet_content_anchor::Anchor<AccountId, RoomId, Balance, BlockNumber>,
+		)> {
+			ContentAnchor::anchors_by_room(room)
+		}
+	}
+
+	impl pallet_rooms_runtime_api::ListenApi<Block, AccountId, Balance> for Runtime {
+		fn room(room: RoomId) -> Option<pallet_rooms::Room<AccountId, Balance>> {
+			Rooms::room(room)
+		}
+
+		fn members(room: RoomId, offset: u32, limit: u32) -> Vec<AccountId> {
+			Rooms::members(room, offset, limit)
+		}
+
+		fn rooms_by_owner(owner: AccountId) -> Vec<RoomId> {
+			Rooms::rooms_by_owner(&owner)
+		}
+
+		fn joined_rooms(who: AccountId) -> Vec<RoomId> {
+			Rooms::joined_rooms(&who)
+		}
+	}
+
+	impl pallet_sponsorship_runtime_api::SponsorshipApi<Block, AccountId, Balance> for Runtime {
+		fn query_info(
+			uxt: <Block as BlockT>::Extrinsic,
+			len: u32,
+		) -> pallet_sponsorship_runtime_api::SponsoredDispatchInfo<AccountId, Balance> {
+			let signer = uxt.signature.as_ref().and_then(|(address, _, extra)| {
+				let who = <Runtime as frame_system::Config>::Lookup::lookup(address.clone()).ok()?;
+				// `ChargeTransactionPayment` encodes as its compact tip and has no getter for it.
+				let tip = Compact::<Balance>::decode(&mut &extra.7.encode()[..]).ok()?;
+				Some((who, tip.0))
+			});
+			let call = uxt.function.clone();
+			let mut info = TransactionPayment::query_info(uxt, len);
+			if let Some((signer, 0)) = &signer {
+				if FreeTx::is_free(signer, &call) {
+					info.partial_fee = 0;
+				}
+			}
+			// Unsigned transactions pay no fees.
+			let payer = signer.map(|(signer, tip)| {
+				Sponsorship::payer(&signer, &call, info.partial_fee, tip)
+			});
+			pallet_sponsorship_runtime_api::SponsoredDispatchInfo { info, payer }
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -484,6 +739,14 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_content_anchor, ContentAnchor);
+			add_benchmark!(params, batches, pallet_free_tx, FreeTx);
+			add_benchmark!(params, batches, pallet_randomness_beacon, RandomnessBeacon);
+			add_benchmark!(params, batches, pallet_rate_limit, RateLimit);
+			add_benchmark!(params, batches, pallet_rooms, Rooms);
+			add_benchmark!(params, batches, pallet_sponsorship, Sponsorship);
+			add_benchmark!(params, batches, pallet_template, TemplateModule);
+			add_benchmark!(params, batches, pallet_welcome_bonus, WelcomeBonus);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
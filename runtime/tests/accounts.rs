@@ -0,0 +1,112 @@
+//! Hooks run when accounts are created and reaped.
+
+mod common;
+
+use codec::Encode;
+use common::{apply, events, next_block, sign, ExtBuilder};
+use frame_support::{assert_ok, traits::Get};
+use node_template_runtime::{
+	Balances, BalancesCall, Call, Event, ExistentialDeposit, FreeTx, NewAccountBonus, RateLimit,
+	Rooms, RoomsCall, SudoCall, TransactionPayment, WelcomeBonus,
+	node_constants::currency::DOLLARS,
+};
+use sp_keyring::AccountKeyring::{Alice, Bob, Dave};
+
+fn transfer_to_dave() -> Call {
+	Call::Balances(BalancesCall::transfer(Dave.to_account_id().into(), DOLLARS))
+}
+
+#[test]
+fn reaped_accounts_leave_no_bookkeeping_behind() {
+	ExtBuilder::default().build_and_execute(|| {
+		let bob = Bob.to_account_id();
+		assert_ok!(apply(Alice, Call::Rooms(RoomsCall::open())).unwrap());
+		assert_ok!(apply(Bob, Call::Rooms(RoomsCall::join(0))).unwrap());
+		assert_eq!(FreeTx::usage(&bob).1, 1);
+		assert_eq!(RateLimit::call_count(&bob).1, 1);
+
+		let reap = Call::Sudo(SudoCall::sudo(Box::new(Call::Balances(
+			BalancesCall::set_balance(bob.clone().into(), 0, 0),
+		))));
+		assert_ok!(apply(Alice, reap).unwrap());
+
+		assert!(events().contains(&Event::frame_system(frame_system::Event::KilledAccount(
+			bob.clone(),
+		))));
+		assert_eq!(FreeTx::usage(&bob), Default::default());
+		assert_eq!(RateLimit::call_count(&bob), Default::default());
+		assert!(events().contains(&Event::pallet_free_tx(pallet_free_tx::RawEvent::UsageCleared(
+			bob.clone(),
+		))));
+		assert!(events().contains(&Event::pallet_rate_limit(
+			pallet_rate_limit::RawEvent::UsageCleared(bob),
+		)));
+	});
+}
+
+#[test]
+fn reaped_members_leave_their_rooms() {
+	ExtBuilder::default().build_and_execute(|| {
+		let bob = Bob.to_account_id();
+		assert_ok!(apply(Alice, Call::Rooms(RoomsCall::open())).unwrap());
+		assert_ok!(apply(Bob, Call::Rooms(RoomsCall::join(0))).unwrap());
+		assert_eq!(Rooms::member_count(), 2);
+
+		let reap = Call::Sudo(SudoCall::sudo(Box::new(Call::Balances(
+			BalancesCall::set_balance(bob.clone().into(), 0, 0),
+		))));
+		assert_ok!(apply(Alice, reap).unwrap());
+
+		assert!(!Rooms::is_member(&bob, 0));
+		assert!(Rooms::joined_rooms(&bob).is_empty());
+		assert_eq!(Rooms::room(0).unwrap().members, 1);
+		assert_eq!(Rooms::member_count(), 1);
+		assert!(events().contains(&Event::pallet_rooms(pallet_rooms::RawEvent::Left(0, bob))));
+	});
+}
+
+#[test]
+fn new_accounts_get_a_welcome_bonus() {
+	ExtBuilder::default()
+		.endow(WelcomeBonus::pot_account(), DOLLARS)
+		.build_and_execute(|| {
+			assert_ok!(apply(Alice, transfer_to_dave()).unwrap());
+			assert_eq!(WelcomeBonus::newcomers(), vec![Dave.to_account_id()]);
+
+			next_block();
+			assert_eq!(
+				Balances::free_balance(Dave.to_account_id()),
+				DOLLARS + NewAccountBonus::get(),
+			);
+			assert_eq!(
+				Balances::free_balance(WelcomeBonus::pot_account()),
+				DOLLARS - NewAccountBonus::get(),
+			);
+		});
+}
+
+#[test]
+fn no_bonus_is_paid_from_an_empty_pot() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(apply(Alice, transfer_to_dave()).unwrap());
+		next_block();
+		assert_eq!(Balances::free_balance(Dave.to_account_id()), DOLLARS);
+	});
+}
+
+#[test]
+fn creating_an_account_costs_more_than_its_welcome_bonus() {
+	ExtBuilder::default().build_and_execute(|| {
+		let dave = Dave.to_account_id();
+		let transfers = vec![
+			BalancesCall::transfer(dave.clone().into(), ExistentialDeposit::get()),
+			BalancesCall::transfer_keep_alive(dave.into(), ExistentialDeposit::get()),
+		];
+		for transfer in transfers {
+			let xt = sign(Alice, Call::Balances(transfer), 0);
+			let fee = TransactionPayment::query_info(xt.clone(), xt.encoded_size() as u32)
+				.partial_fee;
+			assert!(fee > NewAccountBonus::get(), "Fee of {} does not exceed the bonus", fee);
+		}
+	});
+}
@@ -0,0 +1,156 @@
+//! Genesis storage, block production and signed extrinsics for tests of the assembled runtime.
+
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
+use codec::Encode;
+use node_template_runtime::{
+	AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber, Call, Executive, GenesisConfig,
+	GrandpaConfig, Header, Index, SignedExtra, SudoConfig, System, SystemConfig, TimestampCall,
+	UncheckedExtrinsic, SLOT_DURATION, node_constants::currency::DOLLARS,
+};
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_keyring::AccountKeyring;
+use sp_runtime::{
+	generic::{Digest, DigestItem, Era, SignedPayload},
+	traits::Header as _,
+	ApplyExtrinsicResult, BuildStorage,
+};
+
+/// Balance of the accounts endowed by default.
+pub const INITIAL_BALANCE: Balance = 10_000 * DOLLARS;
+
+/// Builds the genesis storage of the runtime.
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, Balance)>,
+	sudo: AccountKeyring,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		ExtBuilder {
+			balances: vec![
+				(AccountKeyring::Alice.to_account_id(), INITIAL_BALANCE),
+				(AccountKeyring::Bob.to_account_id(), INITIAL_BALANCE),
+				(AccountKeyring::Charlie.to_account_id(), INITIAL_BALANCE),
+			],
+			sudo: AccountKeyring::Alice,
+		}
+	}
+}
+
+impl ExtBuilder {
+	/// Endow `who` with `balance` at genesis, on top of the default accounts.
+	pub fn balance(mut self, who: AccountKeyring, balance: Balance) -> Self {
+		self.balances.push((who.to_account_id(), balance));
+		self
+	}
+
+	/// Endow `who`, e.g. a pallet account, with `balance` at genesis.
+	pub fn endow(mut self, who: AccountId, balance: Balance) -> Self {
+		self.balances.push((who, balance));
+		self
+	}
+
+	/// Make `who` the sudo key instead of Alice.
+	pub fn sudo(mut self, who: AccountKeyring) -> Self {
+		self.sudo = who;
+		self
+	}
+
+	/// The externalities of the genesis state.
+	pub fn build(self) -> sp_io::TestExternalities {
+		let storage = GenesisConfig {
+			frame_system: Some(SystemConfig {
+				// The native runtime is executed, the code is never read.
+				code: Vec::new(),
+				changes_trie_config: Default::default(),
+			}),
+			pallet_balances: Some(BalancesConfig { balances: self.balances }),
+			pallet_aura: Some(AuraConfig { authorities: Vec::new() }),
+			pallet_grandpa: Some(GrandpaConfig { authorities: Vec::new() }),
+			pallet_sudo: Some(SudoConfig { key: self.sudo.to_account_id() }),
+		}.build_storage().expect("The genesis config is valid");
+		sp_io::TestExternalities::new(storage)
+	}
+
+	/// Run `test` in block 1 of the genesis state.
+	pub fn build_and_execute(self, test: impl FnOnce()) {
+		self.build().execute_with(|| {
+			initialize_block(1, Default::default());
+			test();
+		})
+	}
+}
+
+/// Start block `number`, with an Aura pre-digest and the timestamp of its slot.
+fn initialize_block(number: BlockNumber, parent_hash: node_template_runtime::Hash) {
+	let slot = number as u64;
+	let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+	Executive::initialize_block(&Header::new(
+		number,
+		Default::default(),
+		Default::default(),
+		parent_hash,
+		digest,
+	));
+	let timestamp = UncheckedExtrinsic::new_unsigned(
+		Call::Timestamp(TimestampCall::set(slot * SLOT_DURATION))
+	);
+	Executive::apply_extrinsic(timestamp)
+		.expect("The timestamp inherent is valid")
+		.expect("The timestamp inherent succeeds");
+}
+
+/// Finalize the current block and start the next one.
+pub fn next_block() {
+	let header = Executive::finalize_block();
+	initialize_block(header.number + 1, header.hash());
+}
+
+/// Finalize blocks until block `number` is the current one.
+pub fn run_to_block(number: BlockNumber) {
+	while System::block_number() < number {
+		next_block();
+	}
+}
+
+/// `call` signed by `signer` with its next nonce, immortal, with `tip`.
+pub fn sign(signer: AccountKeyring, call: Call, tip: Balance) -> UncheckedExtrinsic {
+	let nonce = System::account_nonce(&signer.to_account_id());
+	sign_with_nonce(signer, call, tip, nonce)
+}
+
+/// `call` signed by `signer` with `nonce`, immortal, with `tip`.
+pub fn sign_with_nonce(
+	signer: AccountKeyring,
+	call: Call,
+	tip: Balance,
+	nonce: Index,
+) -> UncheckedExtrinsic {
+	let account = signer.to_account_id();
+	let extra: SignedExtra = (
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(Era::Immortal),
+		frame_system::CheckNonce::from(nonce),
+		frame_system::CheckWeight::new(),
+		pallet_rate_limit::CheckRateLimit::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::from(tip).into(),
+	);
+	let payload = SignedPayload::new(call, extra).expect("The signed extensions are valid");
+	let signature = payload.using_encoded(|payload| signer.sign(payload));
+	let (call, extra, _) = payload.deconstruct();
+	UncheckedExtrinsic::new_signed(call, account.into(), signature.into(), extra)
+}
+
+/// Apply `call` signed by `signer` in the current block.
+pub fn apply(signer: AccountKeyring, call: Call) -> ApplyExtrinsicResult {
+	Executive::apply_extrinsic(sign(signer, call, 0))
+}
+
+/// The events emitted in the current block.
+pub fn events() -> Vec<node_template_runtime::Event> {
+	System::events().into_iter().map(|record| record.event).collect()
+}
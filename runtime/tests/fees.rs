@@ -0,0 +1,133 @@
+//! Fees charged through `ChargeTransactionPaymentOrFree` and the sponsorship adapter.
+
+mod common;
+
+use codec::Encode;
+use common::{apply, next_block, sign, ExtBuilder, INITIAL_BALANCE};
+use frame_support::{assert_ok, traits::Get};
+use node_template_runtime::{
+	Balance, Balances, BalancesCall, Call, ContentAnchor, ContentAnchorCall, ContentHash, Executive,
+	ExistentialDeposit, Rooms, RoomsCall, Sponsorship, SponsorshipCall, System, TransactionPayment,
+	UncheckedExtrinsic,
+	node_constants::currency::{CENTS, DOLLARS},
+};
+use sp_keyring::AccountKeyring::{Alice, Bob, Charlie, Dave};
+
+fn transfer_to_dave(amount: u128) -> Call {
+	Call::Balances(BalancesCall::transfer(Dave.to_account_id().into(), amount))
+}
+
+fn anchor(room: u64, hash: ContentHash) -> Call {
+	Call::ContentAnchor(ContentAnchorCall::anchor(room, hash, 1_024, b"CC-BY-4.0".to_vec()))
+}
+
+fn fee_of(xt: &UncheckedExtrinsic) -> Balance {
+	TransactionPayment::query_info(xt.clone(), xt.encoded_size() as u32).partial_fee
+}
+
+#[test]
+fn room_actions_cost_about_a_cent() {
+	ExtBuilder::default().build_and_execute(|| {
+		let actions = [
+			sign(Alice, anchor(1, ContentHash::Blake2_256([1; 32])), 0),
+			sign(Alice, transfer_to_dave(DOLLARS), 0),
+		];
+		for xt in &actions {
+			let fee = fee_of(xt);
+			assert!((CENTS..=5 * CENTS).contains(&fee), "Fee of {} is out of range", fee);
+		}
+	});
+}
+
+#[test]
+fn signers_pay_the_queried_fee_and_tip() {
+	ExtBuilder::default().build_and_execute(|| {
+		let xt = sign(Alice, transfer_to_dave(DOLLARS), 2_000);
+		let fee = fee_of(&xt);
+		assert!(fee > 0);
+		let issuance = Balances::total_issuance();
+
+		assert_ok!(Executive::apply_extrinsic(xt).unwrap());
+
+		assert_eq!(
+			Balances::free_balance(Alice.to_account_id()),
+			INITIAL_BALANCE - DOLLARS - fee - 2_000,
+		);
+		assert_eq!(Balances::free_balance(Dave.to_account_id()), DOLLARS);
+		// Fees and tips are burned.
+		assert_eq!(Balances::total_issuance(), issuance - fee - 2_000);
+	});
+}
+
+#[test]
+fn fees_are_charged_in_every_block() {
+	ExtBuilder::default().build_and_execute(|| {
+		let mut paid = 0;
+		for _ in 0..3 {
+			let xt = sign(Alice, transfer_to_dave(CENTS), 0);
+			paid += fee_of(&xt);
+			assert_ok!(Executive::apply_extrinsic(xt).unwrap());
+			next_block();
+		}
+		assert_eq!(
+			Balances::free_balance(Alice.to_account_id()),
+			INITIAL_BALANCE - 3 * CENTS - paid,
+		);
+	});
+}
+
+#[test]
+fn whitelisted_calls_are_free_for_accounts_holding_the_existential_deposit() {
+	let ed = ExistentialDeposit::get();
+	ExtBuilder::default().balance(Dave, ed).build_and_execute(|| {
+		assert_ok!(apply(Bob, Call::Rooms(RoomsCall::open())).unwrap());
+		assert_ok!(apply(Dave, Call::Rooms(RoomsCall::join(0))).unwrap());
+		assert!(Rooms::is_member(&Dave.to_account_id(), 0));
+		assert_eq!(Balances::free_balance(Dave.to_account_id()), ed);
+	});
+}
+
+#[test]
+fn accounts_that_do_not_exist_get_no_free_calls() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(apply(Bob, Call::Rooms(RoomsCall::open())).unwrap());
+		assert!(apply(Dave, Call::Rooms(RoomsCall::join(0))).is_err());
+		assert!(!System::account_exists(&Dave.to_account_id()));
+	});
+}
+
+#[test]
+fn sponsors_pay_for_calls_of_members_in_their_room() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(apply(Bob, Call::Rooms(RoomsCall::open())).unwrap());
+		assert_ok!(apply(Alice, Call::Rooms(RoomsCall::join(0))).unwrap());
+		assert_ok!(apply(Bob, Call::Sponsorship(SponsorshipCall::fund(0, DOLLARS))).unwrap());
+
+		let hash = ContentHash::Blake2_256([1; 32]);
+		let deposit = ContentAnchor::deposit_for(&hash, b"CC-BY-4.0");
+		let xt = sign(Alice, anchor(0, hash), 0);
+		let fee = fee_of(&xt);
+		assert_ok!(Executive::apply_extrinsic(xt).unwrap());
+
+		assert_eq!(Sponsorship::budget(0), DOLLARS - fee);
+		assert_eq!(Balances::free_balance(Alice.to_account_id()), INITIAL_BALANCE - deposit);
+		assert_eq!(Balances::reserved_balance(Alice.to_account_id()), deposit);
+
+		// Non-members pay for themselves.
+		let xt = sign(Charlie, anchor(0, ContentHash::Blake2_256([2; 32])), 0);
+		let fee = fee_of(&xt);
+		assert_ok!(Executive::apply_extrinsic(xt).unwrap());
+		assert_eq!(Sponsorship::budget(0), DOLLARS - fee);
+	});
+}
+
+#[test]
+fn only_room_owners_can_sponsor() {
+	ExtBuilder::default().build_and_execute(|| {
+		let fund = || Call::Sponsorship(SponsorshipCall::fund(0, DOLLARS));
+		assert!(apply(Bob, fund()).unwrap().is_err());
+		assert_ok!(apply(Alice, Call::Rooms(RoomsCall::open())).unwrap());
+		assert!(apply(Bob, fund()).unwrap().is_err());
+		assert_ok!(apply(Alice, fund()).unwrap());
+	});
+}
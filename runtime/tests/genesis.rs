@@ -0,0 +1,34 @@
+//! The genesis state built from `GenesisConfig`.
+
+mod common;
+
+use common::{ExtBuilder, INITIAL_BALANCE};
+use node_template_runtime::{node_constants::currency::DOLLARS, Balances, Sudo, System};
+use sp_keyring::AccountKeyring::{Alice, Bob, Charlie, Dave};
+
+#[test]
+fn endows_accounts() {
+	ExtBuilder::default().balance(Dave, DOLLARS).build().execute_with(|| {
+		for who in &[Alice, Bob, Charlie] {
+			assert_eq!(Balances::free_balance(who.to_account_id()), INITIAL_BALANCE);
+		}
+		assert_eq!(Balances::free_balance(Dave.to_account_id()), DOLLARS);
+		assert_eq!(Balances::total_issuance(), 3 * INITIAL_BALANCE + DOLLARS);
+	});
+}
+
+#[test]
+fn sets_the_sudo_key() {
+	ExtBuilder::default().sudo(Bob).build().execute_with(|| {
+		assert_eq!(Sudo::key(), Bob.to_account_id());
+	});
+}
+
+#[test]
+fn starts_at_block_one() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(System::block_number(), 1);
+		common::run_to_block(5);
+		assert_eq!(System::block_number(), 5);
+	});
+}
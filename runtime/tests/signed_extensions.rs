@@ -0,0 +1,148 @@
+//! Checks of the `SignedExtra` tuple when applying extrinsics through `Executive`.
+
+mod common;
+
+use common::{apply, events, next_block, run_to_block, sign, sign_with_nonce, ExtBuilder};
+use frame_support::{assert_ok, traits::{Filter, Get}};
+use node_template_runtime::{
+	Balances, BalancesCall, Call, CallRoom, ContentAnchorCall, ContentHash, Event, Executive,
+	FreeCalls, MaxCallsPerWindow, RateLimitWindow, RateLimitedCalls, RoomsCall, SudoCall, System,
+	UncheckedExtrinsic, node_constants::currency::DOLLARS,
+};
+use pallet_sponsorship::CallRoom as _;
+use sp_keyring::AccountKeyring::{Alice, Bob, Charlie, Dave};
+use sp_runtime::transaction_validity::{
+	InvalidTransaction, TransactionSource, TransactionValidityError,
+};
+
+fn remark() -> Call {
+	Call::System(frame_system::Call::remark(Vec::new()))
+}
+
+fn invalid(error: InvalidTransaction) -> TransactionValidityError {
+	TransactionValidityError::Invalid(error)
+}
+
+#[test]
+fn nonces_must_follow_each_other() {
+	ExtBuilder::default().build_and_execute(|| {
+		let first = sign(Alice, remark(), 0);
+		assert_ok!(Executive::apply_extrinsic(first.clone()).unwrap());
+		assert_eq!(Executive::apply_extrinsic(first), Err(invalid(InvalidTransaction::Stale)));
+		assert_ok!(apply(Alice, remark()).unwrap());
+		assert_eq!(System::account_nonce(Alice.to_account_id()), 2);
+	});
+}
+
+#[test]
+fn signatures_are_checked() {
+	ExtBuilder::default().build_and_execute(|| {
+		let xt = sign(Alice, remark(), 0);
+		let (_, signature, extra) = xt.signature.unwrap();
+		let forged = UncheckedExtrinsic::new_signed(
+			xt.function, Bob.to_account_id().into(), signature, extra,
+		);
+		assert_eq!(Executive::apply_extrinsic(forged), Err(invalid(InvalidTransaction::BadProof)));
+	});
+}
+
+#[test]
+fn accounts_without_funds_cannot_pay_fees() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(apply(Dave, remark()), Err(invalid(InvalidTransaction::Payment)));
+	});
+}
+
+#[test]
+fn room_actions_are_rate_limited_per_window() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(apply(Charlie, Call::Rooms(RoomsCall::open())).unwrap());
+		// Alternately join and leave the room.
+		let call = |i: u32| if i % 2 == 0 {
+			Call::Rooms(RoomsCall::join(0))
+		} else {
+			Call::Rooms(RoomsCall::leave(0))
+		};
+		let limit = MaxCallsPerWindow::get();
+		for i in 0..limit {
+			assert_ok!(apply(Alice, call(i)).unwrap());
+		}
+		assert_eq!(
+			apply(Alice, call(limit)),
+			Err(invalid(InvalidTransaction::Custom(pallet_rate_limit::RATE_LIMITED))),
+		);
+		// Other accounts and calls are not limited.
+		assert_ok!(apply(Bob, call(0)).unwrap());
+		assert_ok!(apply(Alice, remark()).unwrap());
+
+		next_block();
+		assert!(apply(Alice, call(limit)).is_err());
+		run_to_block(RateLimitWindow::get());
+		assert_ok!(apply(Alice, call(limit)).unwrap());
+	});
+}
+
+#[test]
+fn pending_room_actions_of_an_account_do_not_replace_each_other() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(apply(Charlie, Call::Rooms(RoomsCall::open())).unwrap());
+		// Tipped, so that the free call quota does not come into play.
+		let nonce = System::account_nonce(Alice.to_account_id());
+		let join = sign_with_nonce(Alice, Call::Rooms(RoomsCall::join(0)), 1, nonce);
+		let leave = sign_with_nonce(Alice, Call::Rooms(RoomsCall::leave(0)), 1, nonce + 1);
+
+		let join = Executive::validate_transaction(TransactionSource::External, join).unwrap();
+		let leave = Executive::validate_transaction(TransactionSource::External, leave).unwrap();
+		assert!(join.provides.iter().all(|tag| !leave.provides.contains(tag)));
+		assert_eq!(leave.requires, join.provides);
+	});
+}
+
+#[test]
+fn only_the_sudo_key_dispatches_as_root() {
+	ExtBuilder::default().build_and_execute(|| {
+		let set_balance = || Call::Sudo(SudoCall::sudo(Box::new(Call::Balances(
+			BalancesCall::set_balance(Dave.to_account_id().into(), DOLLARS, 0),
+		))));
+
+		// The extrinsic is valid and paid for, but its dispatch fails.
+		assert!(apply(Bob, set_balance()).unwrap().is_err());
+		assert_ok!(apply(Alice, set_balance()).unwrap());
+		assert!(events().contains(&Event::pallet_sudo(pallet_sudo::Event::Sudid(Ok(())))));
+		assert_eq!(Balances::free_balance(Dave.to_account_id()), DOLLARS);
+	});
+}
+
+#[test]
+fn whitelisted_calls_are_those_of_rooms() {
+	let transfer = Call::Balances(BalancesCall::transfer(Bob.to_account_id().into(), 1));
+	assert!(FreeCalls::filter(&Call::Rooms(RoomsCall::join(0))));
+	assert!(FreeCalls::filter(&Call::Rooms(RoomsCall::leave(0))));
+	assert!(!FreeCalls::filter(&Call::Rooms(RoomsCall::open())));
+	assert!(!FreeCalls::filter(&remark()));
+	assert!(!FreeCalls::filter(&transfer));
+	let template = Call::TemplateModule(pallet_template::Call::do_something(1));
+	assert!(!FreeCalls::filter(&template));
+	assert!(!RateLimitedCalls::filter(&template));
+}
+
+#[test]
+fn sponsored_calls_are_rate_limited() {
+	ExtBuilder::default().build_and_execute(|| {
+		let hash = ContentHash::Blake2_256([1; 32]);
+		assert_ok!(apply(Alice, Call::ContentAnchor(
+			ContentAnchorCall::anchor(0, hash.clone(), 1, Vec::new())
+		)).unwrap());
+
+		let room_calls = [
+			Call::ContentAnchor(ContentAnchorCall::anchor(0, hash.clone(), 1, Vec::new())),
+			Call::ContentAnchor(ContentAnchorCall::remove(hash)),
+			Call::Rooms(RoomsCall::join(0)),
+			Call::Rooms(RoomsCall::leave(0)),
+		];
+		for call in &room_calls {
+			assert!(CallRoom::room_of(call).is_some());
+			assert!(RateLimitedCalls::filter(call), "{:?} is sponsored but not rate limited", call);
+		}
+	});
+}
@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+
+set -e
+
+# Regenerate the `weights.rs` of custom pallets from their benchmarks, e.g.
+# `./scripts/benchmark.sh pallet_template`. Without arguments, every pallet under `pallets/` with a
+# `benchmarking.rs` is regenerated.
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+if [ $# -eq 0 ]; then
+  for DIR in pallets/*/; do
+    if [ -f ${DIR}src/benchmarking.rs ]; then
+      set -- "$@" pallet_$(basename ${DIR} | tr '-' '_')
+    fi
+  done
+fi
+
+cargo build --release --features runtime-benchmarks
+
+for PALLET in "$@"; do
+  PALLET_DIR=$(echo ${PALLET#pallet_} | tr '_' '-')
+
+  echo "*** Benchmarking ${PALLET} ***"
+
+  ./target/release/node-template benchmark \
+    --chain=dev \
+    --steps=50 \
+    --repeat=20 \
+    --pallet=${PALLET} \
+    --extrinsic='*' \
+    --execution=wasm \
+    --wasm-execution=compiled \
+    --heap-pages=4096 \
+    --output=./pallets/${PALLET_DIR}/src/weights.rs \
+    --template=./scripts/frame-weight-template.hbs
+done